@@ -0,0 +1,90 @@
+//! Parsing the day 5 moves, against the original parser compiling its regex for every line.
+//! Run with `cargo bench --bench day5_actions`
+
+use std::hint::black_box;
+
+use aoc::{input, solution::Solution, CraneType, CrateAction, Day5, Warehouse};
+use criterion::{criterion_group, criterion_main, Criterion};
+
+/// The original `CrateAction::from_str`, building the regex for every move
+fn parse_per_line(line: &str) -> Option<CrateAction> {
+    let re = regex::Regex::new(r"move (?P<move>\d+) from (?P<from>\d+) to (?P<to>\d+)").unwrap();
+    let value = re.captures_iter(line).next()?;
+    Some(CrateAction::new(
+        value["move"].parse().ok()?,
+        value["from"].parse().ok()?,
+        value["to"].parse().ok()?,
+    ))
+}
+
+/// Part A with the moves read by the original parser
+fn solve_per_line(values: &str) -> String {
+    let (drawing, moves) = values.split_once("\n\n").unwrap();
+    let mut warehouse: Warehouse = drawing.parse().unwrap();
+    let actions = moves
+        .lines()
+        .filter_map(parse_per_line)
+        .collect::<Vec<CrateAction>>();
+    warehouse.operate(&actions, &CraneType::Lame);
+    warehouse.top_crates()
+}
+
+/// A drawing of 9 stacks followed by `moves` moves
+fn generate(moves: usize) -> String {
+    let mut input =
+        "[A] [B] [C] [D] [E] [F] [G] [H] [I]\n 1   2   3   4   5   6   7   8   9 \n".to_string();
+    for n in 0..moves {
+        input.push_str(&format!(
+            "\nmove {} from {} to {}",
+            n % 40 + 1,
+            n % 9 + 1,
+            (n + 4) % 9 + 1
+        ));
+    }
+    input
+}
+
+fn puzzle(c: &mut Criterion) {
+    let expected = aoc::crates_on_top_of_each_stack(input::DAY5).unwrap();
+    assert_eq!(expected, solve_per_line(input::DAY5));
+    let mut group = c.benchmark_group("day 5 part A");
+    group.bench_function("regex per line", |b| {
+        b.iter(|| solve_per_line(black_box(input::DAY5)))
+    });
+    group.bench_function("current", |b| {
+        b.iter(|| aoc::crates_on_top_of_each_stack(black_box(input::DAY5)))
+    });
+    group.finish();
+}
+
+/// The parsers on `moves` generated moves, the original one only when it takes seconds at most
+fn generated(c: &mut Criterion, moves: usize, per_line: bool) {
+    let input = generate(moves);
+    let mut group = c.benchmark_group(format!("{} moves", moves));
+    group.sample_size(10);
+    if per_line {
+        let lines = input.split_once("\n\n").unwrap().1;
+        group.bench_function("regex per line", |b| {
+            b.iter(|| assert_eq!(moves, lines.lines().filter_map(parse_per_line).count()))
+        });
+    }
+    group.bench_function("Day5::parse", |b| {
+        b.iter(|| assert_eq!(moves, Day5::parse(black_box(&input)).unwrap().1.len()))
+    });
+    group.bench_function("read_crane_actions", |b| {
+        b.iter(|| {
+            let actions = aoc::read_crane_actions(black_box(input.as_bytes()));
+            assert_eq!(moves, actions.filter(|action| action.is_ok()).count())
+        })
+    });
+    group.finish();
+}
+
+fn moves(c: &mut Criterion) {
+    // the original parser needs about 250µs a move
+    generated(c, 10_000, true);
+    generated(c, 1_000_000, false);
+}
+
+criterion_group!(benches, puzzle, moves);
+criterion_main!(benches);
@@ -0,0 +1,242 @@
+//! Command-line runner for the Advent of Code 2022 puzzles
+//!
+//! ```text
+//! aoc [--strict] <day> <a|b> [input-path]   solve one part, reading stdin for `-` and `dayN.txt`
+//!                                           from `$AOC_INPUT_DIR` or the embedded inputs when no
+//!                                           path is given
+//! aoc [--strict] --all [input-dir]          solve every day, reading `dayN.txt` from `input-dir`,
+//!                                           `$AOC_INPUT_DIR` or the embedded inputs
+//! ```
+//!
+//! Invalid lines are skipped and reported on stderr, unless `--strict` is given.
+
+use std::{
+    fs,
+    io::{self, Read},
+    process::ExitCode,
+    time::{Duration, Instant},
+};
+
+use aoc::{
+    input::InputProvider,
+    solution::{self, Mode, Part, PUZZLES},
+};
+
+const USAGE: &str =
+    "usage: aoc [--strict] <day> <a|b> [input-path]\n       aoc [--strict] --all [input-dir]";
+
+/// Where the input of a single part comes from
+#[derive(Debug, PartialEq, Eq)]
+enum Input {
+    /// `dayN.txt` of `$AOC_INPUT_DIR`, or the embedded input
+    Provided,
+    Stdin,
+    File(String),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Command {
+    Single {
+        day: usize,
+        part: Part,
+        input: Input,
+        mode: Mode,
+    },
+    All {
+        dir: Option<String>,
+        mode: Mode,
+    },
+}
+
+impl TryFrom<Vec<String>> for Command {
+    type Error = String;
+
+    fn try_from(args: Vec<String>) -> Result<Self, Self::Error> {
+        let mut args = args.iter().map(|a| a.as_str()).peekable();
+        let mode = match args.next_if_eq(&"--strict") {
+            Some(_) => Mode::Strict,
+            None => Mode::Lenient,
+        };
+        match (args.next(), args.next(), args.next(), args.next()) {
+            (Some("--all"), dir, None, None) => Ok(Command::All {
+                dir: dir.map(|d| d.to_string()),
+                mode,
+            }),
+            (Some(day), Some(part), path, None) => Ok(Command::Single {
+                day: day.parse().map_err(|_| format!("invalid day '{}'", day))?,
+                part: part.parse().map_err(|e: aoc::Ooops| e.to_string())?,
+                input: match path {
+                    None => Input::Provided,
+                    Some("-") => Input::Stdin,
+                    Some(path) => Input::File(path.to_string()),
+                },
+                mode,
+            }),
+            _ => Err(USAGE.to_string()),
+        }
+    }
+}
+
+fn read_input(day: usize, input: &Input) -> Result<String, String> {
+    match input {
+        Input::Provided => InputProvider::from_env()
+            .get(day)
+            .map_err(|e| e.to_string()),
+        Input::File(path) => {
+            fs::read_to_string(path).map_err(|e| format!("cannot read {}: {}", path, e))
+        }
+        Input::Stdin => {
+            let mut values = String::new();
+            io::stdin()
+                .read_to_string(&mut values)
+                .map_err(|e| format!("cannot read stdin: {}", e))?;
+            Ok(values)
+        }
+    }
+}
+
+fn timed<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
+
+fn run_all(provider: &InputProvider, mode: Mode) {
+    println!(
+        "{:>3}  {:<4}  {:<24}  {:>12}",
+        "day", "part", "answer", "time"
+    );
+    let mut total = Duration::ZERO;
+    for puzzle in PUZZLES {
+        let values = match provider.get(puzzle.day()) {
+            Ok(values) => values,
+            Err(e) => {
+                eprintln!("{}", e);
+                continue;
+            }
+        };
+        for part in [Part::A, Part::B] {
+            let (answer, elapsed) = timed(|| puzzle.run_with(part, &values, mode));
+            total += elapsed;
+            let answer = answer.map_or_else(
+                |e| format!("error: {}", e),
+                |d| match d.skipped.len() {
+                    0 => d.answer,
+                    skipped => format!("{} ({} skipped)", d.answer, skipped),
+                },
+            );
+            println!(
+                "{:>3}  {:<4}  {:<24}  {:>12}",
+                puzzle.day(),
+                part,
+                answer,
+                format!("{:.2?}", elapsed)
+            );
+        }
+    }
+    println!(
+        "{:>3}  {:<4}  {:<24}  {:>12}",
+        "",
+        "",
+        "",
+        format!("{:.2?}", total)
+    );
+}
+
+fn main() -> ExitCode {
+    let command = match Command::try_from(std::env::args().skip(1).collect::<Vec<String>>()) {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::from(2);
+        }
+    };
+    match command {
+        Command::All { dir, mode } => {
+            let provider = dir.map_or_else(InputProvider::from_env, InputProvider::new);
+            run_all(&provider, mode);
+            ExitCode::SUCCESS
+        }
+        Command::Single {
+            day,
+            part,
+            input,
+            mode,
+        } => {
+            match read_input(day, &input).and_then(|values| {
+                solution::run_with(day, part, &values, mode).map_err(|e| e.to_string())
+            }) {
+                Ok(diagnosed) => {
+                    for skipped in diagnosed.skipped {
+                        eprintln!("skipped {}", skipped);
+                    }
+                    println!("{}", diagnosed.answer);
+                    ExitCode::SUCCESS
+                }
+                Err(e) => {
+                    eprintln!("{}", e);
+                    ExitCode::FAILURE
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn parse_command() {
+        assert_eq!(
+            Ok(Command::All {
+                dir: None,
+                mode: Mode::Lenient
+            }),
+            Command::try_from(args(&["--all"]))
+        );
+        assert_eq!(
+            Ok(Command::All {
+                dir: Some("inputs".to_string()),
+                mode: Mode::Strict
+            }),
+            Command::try_from(args(&["--strict", "--all", "inputs"]))
+        );
+        assert_eq!(
+            Ok(Command::Single {
+                day: 5,
+                part: Part::B,
+                input: Input::File("day5.txt".to_string()),
+                mode: Mode::Lenient
+            }),
+            Command::try_from(args(&["5", "b", "day5.txt"]))
+        );
+        assert_eq!(
+            Ok(Command::Single {
+                day: 9,
+                part: Part::A,
+                input: Input::Stdin,
+                mode: Mode::Lenient
+            }),
+            Command::try_from(args(&["9", "a", "-"]))
+        );
+        assert_eq!(
+            Ok(Command::Single {
+                day: 9,
+                part: Part::A,
+                input: Input::Provided,
+                mode: Mode::Strict
+            }),
+            Command::try_from(args(&["--strict", "9", "a"]))
+        );
+        assert_eq!(
+            Err("invalid day 'x'".to_string()),
+            Command::try_from(args(&["x", "a"]))
+        );
+        assert_eq!(Err(USAGE.to_string()), Command::try_from(args(&[])));
+    }
+}
@@ -1,13 +1,73 @@
-pub(crate) fn group_max(values: &'_ str) -> impl Iterator<Item = usize> + '_ {
-    values
-        .split("\n\n")
-        .map(|v| v.trim())
-        .filter(|v| !v.is_empty())
-        .map(|v| {
-            v.lines()
-                .map(|v| v.trim())
-                .filter(|v| !v.is_empty())
-                .filter_map(|v| v.parse::<usize>().ok())
-                .sum()
-        })
+use crate::{
+    error::Ooops,
+    solution::{Diagnostics, Solution},
+};
+
+pub(crate) fn group_max(values: &str, diagnostics: &mut Diagnostics) -> Result<Vec<usize>, Ooops> {
+    let mut groups = vec![];
+    let mut current: Option<usize> = None;
+    for (index, line) in values.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            groups.extend(current.take());
+            continue;
+        }
+        let calories = match line.parse::<usize>() {
+            Ok(calories) => calories,
+            Err(e) => {
+                diagnostics.skip(
+                    Ooops::parse("calories", line)
+                        .caused_by(e)
+                        .at_line(index + 1),
+                )?;
+                0
+            }
+        };
+        current = Some(current.unwrap_or(0) + calories);
+    }
+    groups.extend(current);
+    Ok(groups)
+}
+
+/// Calorie Counting -> <https://adventofcode.com/2022/day/1>
+pub struct Day1;
+
+impl Solution for Day1 {
+    const DAY: usize = 1;
+    type Input = Vec<usize>;
+    type Output = usize;
+
+    fn parse_with(values: &str, diagnostics: &mut Diagnostics) -> Result<Self::Input, Ooops> {
+        group_max(values, diagnostics)
+    }
+
+    fn part_a(input: &Self::Input) -> Result<Self::Output, Ooops> {
+        Ok(input.iter().copied().fold(0, usize::max))
+    }
+
+    fn part_b(input: &Self::Input) -> Result<Self::Output, Ooops> {
+        let mut values = input.clone();
+        values.sort();
+        values.reverse();
+        Ok(values.iter().take(3).sum())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::solution::Mode;
+
+    use super::*;
+
+    #[test]
+    fn group_calories() {
+        let mut diagnostics = Diagnostics::new(Mode::Lenient);
+        assert_eq!(
+            Ok(vec![3, 0, 7]),
+            group_max("1\n2\n\nx\n\n\n3\n4\n", &mut diagnostics)
+        );
+        assert_eq!(1, diagnostics.skipped().len());
+        assert_eq!(Some(4), diagnostics.skipped()[0].line);
+        assert!(group_max("1\nx\n", &mut Diagnostics::new(Mode::Strict)).is_err());
+    }
 }
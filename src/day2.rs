@@ -1,8 +1,11 @@
 use std::str::FromStr;
 
-use crate::error::Ooops;
+use crate::{
+    error::Ooops,
+    solution::{Diagnostics, Part, Solution},
+};
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone, Copy)]
 enum Hand {
     Rock,
     Paper,
@@ -65,25 +68,25 @@ impl Hand {
 }
 
 #[derive(Debug, PartialEq)]
-pub(crate) struct RpsMatch {
+pub struct RpsMatch {
     mine: Hand,
     opponent: Hand,
 }
 
 impl RpsMatch {
-    pub(crate) fn play(self) -> usize {
+    pub(crate) fn play(&self) -> usize {
         self.mine.weight() + self.mine.fight(&self.opponent).value()
     }
 }
 
 #[derive(Debug, PartialEq)]
-pub(crate) struct CheatRpsMatch {
+pub struct CheatRpsMatch {
     opponent: Hand,
     result: RpsMatchResult,
 }
 
 impl CheatRpsMatch {
-    pub(crate) fn play(self) -> usize {
+    pub(crate) fn play(&self) -> usize {
         let my_hand = self.result.hand(&self.opponent);
         my_hand.weight() + self.result.value()
     }
@@ -97,7 +100,7 @@ impl FromStr for RpsMatchResult {
             "X" => Ok(Self::Loser),
             "Y" => Ok(Self::Draw),
             "Z" => Ok(Self::Winner),
-            _ => Err(Ooops(format!("[a] invalid s='{}'", s))),
+            _ => Err(Ooops::parse("result", s)),
         }
     }
 }
@@ -110,7 +113,7 @@ impl FromStr for Hand {
             "A" | "X" => Ok(Self::Rock),
             "B" | "Y" => Ok(Self::Paper),
             "C" | "Z" => Ok(Self::Scisor),
-            _ => Err(Ooops(format!("[b] invalid s='{}'", s))),
+            _ => Err(Ooops::parse("hand", s)),
         }
     }
 }
@@ -125,7 +128,7 @@ impl FromStr for RpsMatch {
                 mine: mine.parse()?,
                 opponent: opponents.parse()?,
             }),
-            (_, _) => Err(Ooops(format!("[c] missing value '{}'", s))),
+            (_, _) => Err(Ooops::parse("match", s)),
         }
     }
 }
@@ -140,13 +143,111 @@ impl FromStr for CheatRpsMatch {
                 opponent: opponents.parse()?,
                 result: result.parse()?,
             }),
-            (_, _) => Err(Ooops(format!("[e] missing value '{}'", s))),
+            (_, _) => Err(Ooops::parse("match", s)),
         }
     }
 }
 
+/// A line of the strategy guide, the second column being read differently by each part
+#[derive(Debug, PartialEq)]
+pub struct Round {
+    opponent: Hand,
+    second: String,
+}
+
+impl Round {
+    /// The two columns, the second one not read yet
+    fn read(s: &str) -> Result<Self, Ooops> {
+        let mut values = s.split(' ').take(2);
+        match (values.next(), values.next()) {
+            (Some(opponent), Some(second)) => Ok(Self {
+                opponent: opponent.parse()?,
+                second: second.to_string(),
+            }),
+            (_, _) => Err(Ooops::parse("match", s)),
+        }
+    }
+
+    /// The second column as my hand, for part A
+    fn rps_match(&self) -> Result<RpsMatch, Ooops> {
+        Ok(RpsMatch {
+            mine: self.second.parse()?,
+            opponent: self.opponent,
+        })
+    }
+
+    /// The second column as the result to reach, for part B
+    fn cheat_match(&self) -> Result<CheatRpsMatch, Ooops> {
+        Ok(CheatRpsMatch {
+            opponent: self.opponent,
+            result: self.second.parse()?,
+        })
+    }
+}
+
+/// Valid when at least one part can read the second column
+impl FromStr for Round {
+    type Err = Ooops;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let round = Self::read(s)?;
+        round
+            .rps_match()
+            .map(|_| ())
+            .or_else(|_| round.cheat_match().map(|_| ()))?;
+        Ok(round)
+    }
+}
+
+/// Rock Paper Scissors -> <https://adventofcode.com/2022/day/2>
+pub struct Day2;
+
+impl Solution for Day2 {
+    const DAY: usize = 2;
+    type Input = Vec<Round>;
+    type Output = usize;
+
+    fn parse_with(values: &str, diagnostics: &mut Diagnostics) -> Result<Self::Input, Ooops> {
+        diagnostics.parse_lines(values, |line| line.parse())
+    }
+
+    /// Lines whose second column the part cannot read are invalid
+    fn parse_part(
+        values: &str,
+        part: Part,
+        diagnostics: &mut Diagnostics,
+    ) -> Result<Self::Input, Ooops> {
+        diagnostics.parse_lines(values, |line| {
+            let round = Round::read(line)?;
+            match part {
+                Part::A => round.rps_match().map(|_| ()),
+                Part::B => round.cheat_match().map(|_| ()),
+            }?;
+            Ok(round)
+        })
+    }
+
+    /// Fails on a round whose second column is not a hand
+    fn part_a(input: &Self::Input) -> Result<Self::Output, Ooops> {
+        input
+            .iter()
+            .map(|round| round.rps_match().map(|v| v.play()))
+            .sum()
+    }
+
+    /// Fails on a round whose second column is not a result
+    fn part_b(input: &Self::Input) -> Result<Self::Output, Ooops> {
+        input
+            .iter()
+            .map(|round| round.cheat_match().map(|v| v.play()))
+            .sum()
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use crate::solution::{Mode, Part};
+
     use super::*;
 
     #[test]
@@ -162,4 +263,35 @@ mod tests {
         assert_eq!(1, "B X".parse::<CheatRpsMatch>().unwrap().play());
         assert_eq!(7, "C Z".parse::<CheatRpsMatch>().unwrap().play());
     }
+
+    #[test]
+    fn second_column_valid_for_one_part() {
+        let values = "A Y\nA A\nA Q\n";
+        let solve = |part, mode| Day2::solve_with(values, part, mode).map(|d| d.answer);
+        assert_eq!(Ok(12), solve(Part::A, Mode::Lenient));
+        assert_eq!(Ok(4), solve(Part::B, Mode::Lenient));
+        assert_eq!(
+            Err(Ooops::parse("hand", "Q").at_line(3).in_day(2)),
+            solve(Part::A, Mode::Strict)
+        );
+        assert_eq!(
+            Err(Ooops::parse("result", "A").at_line(2).in_day(2)),
+            solve(Part::B, Mode::Strict)
+        );
+        assert_eq!(
+            Ok(12),
+            Day2::solve_with("A Y\nA A", Part::A, Mode::Strict).map(|d| d.answer)
+        );
+        let lenient = Day2::solve_with(values, Part::B, Mode::Lenient).unwrap();
+        assert_eq!(
+            vec![
+                Ooops::parse("result", "A").at_line(2).in_day(2),
+                Ooops::parse("result", "Q").at_line(3).in_day(2)
+            ],
+            lenient.skipped
+        );
+        // parsed for no part in particular, a part fails on the rounds it cannot read
+        let rounds = Day2::parse(values).unwrap();
+        assert_eq!(Err(Ooops::parse("result", "A")), Day2::part_b(&rounds));
+    }
 }
@@ -1,9 +1,12 @@
 use std::{collections::HashSet, str::FromStr};
 
-use crate::error::Ooops;
+use crate::{
+    error::Ooops,
+    solution::{Diagnostics, Solution},
+};
 
 #[derive(Debug, PartialEq, Eq)]
-pub(crate) struct Rucksack {
+pub struct Rucksack {
     compartment_a: String,
     compartment_b: String,
     pub(crate) shared: HashSet<char>,
@@ -31,11 +34,8 @@ impl FromStr for Rucksack {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let (compartment_a, compartment_b) = s.split_at(s.len() / 2);
-        if compartment_a.len() != compartment_b.len() {
-            return Err(Ooops(format!(
-                "compartments don't have the same number of elements. {}:{}",
-                compartment_a, compartment_b
-            )));
+        if compartment_a.len() != compartment_b.len() || s.chars().any(|c| priority(&c).is_err()) {
+            return Err(Ooops::parse("rucksack", s));
         }
         let mut shared = HashSet::new();
         for c in compartment_a.chars() {
@@ -54,11 +54,45 @@ impl FromStr for Rucksack {
 pub(crate) fn priority(c: &char) -> Result<usize, Ooops> {
     let index = " abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ".find(*c);
     if index.is_none() {
-        return Err(Ooops(format!("{} is not valid", c)));
+        return Err(Ooops::parse("item type", c.to_string()));
     }
     Ok(index.unwrap())
 }
 
+/// Rucksack Reorganization -> <https://adventofcode.com/2022/day/3>
+pub struct Day3;
+
+impl Solution for Day3 {
+    const DAY: usize = 3;
+    type Input = Vec<Rucksack>;
+    type Output = usize;
+
+    fn parse_with(values: &str, diagnostics: &mut Diagnostics) -> Result<Self::Input, Ooops> {
+        diagnostics.parse_lines(values, str::parse)
+    }
+
+    fn part_a(input: &Self::Input) -> Result<Self::Output, Ooops> {
+        Ok(input
+            .iter()
+            .map(|v| {
+                v.shared
+                    .iter()
+                    .map(priority)
+                    .filter_map(|v| v.ok())
+                    .sum::<usize>()
+            })
+            .sum())
+    }
+
+    fn part_b(input: &Self::Input) -> Result<Self::Output, Ooops> {
+        Ok(input
+            .chunks_exact(3)
+            .map(|group| group[0].intersection(vec![&group[1], &group[2]]))
+            .map(|v| v.iter().filter_map(|v| priority(v).ok()).sum::<usize>())
+            .sum())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
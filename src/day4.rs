@@ -1,6 +1,9 @@
 use std::str::FromStr;
 
-use crate::error::Ooops;
+use crate::{
+    error::Ooops,
+    solution::{Diagnostics, Solution},
+};
 
 #[derive(Debug, PartialEq, Eq)]
 pub(crate) struct AssignmentRange {
@@ -26,19 +29,19 @@ impl FromStr for AssignmentRange {
         let values = s.split('-');
         let values = values.collect::<Vec<&str>>();
         let mut values = values.iter();
-        let map_err = |e| Ooops(format!("{}", e));
+        let map_err = |e| Ooops::parse("range", s).caused_by(e);
         match (values.next(), values.next()) {
             (Some(start), Some(end)) => Ok(AssignmentRange {
                 start: start.parse().map_err(map_err)?,
                 end: end.parse().map_err(map_err)?,
             }),
-            (_, _) => Err(Ooops(format!("bad range {}", s))),
+            (_, _) => Err(Ooops::parse("range", s)),
         }
     }
 }
 
 #[derive(Debug, PartialEq, Eq)]
-pub(crate) struct AssignmentPair {
+pub struct AssignmentPair {
     pub(crate) a: AssignmentRange,
     pub(crate) b: AssignmentRange,
 }
@@ -55,19 +58,52 @@ impl FromStr for AssignmentPair {
                 a: a.parse()?,
                 b: b.parse()?,
             }),
-            (_, _) => Err(Ooops(format!("bad pair {}", s))),
+            (_, _) => Err(Ooops::parse("pair", s)),
         }
     }
 }
 
+/// Camp Cleanup -> <https://adventofcode.com/2022/day/4>
+pub struct Day4;
+
+impl Solution for Day4 {
+    const DAY: usize = 4;
+    type Input = Vec<AssignmentPair>;
+    type Output = usize;
+
+    fn parse_with(values: &str, diagnostics: &mut Diagnostics) -> Result<Self::Input, Ooops> {
+        diagnostics.parse_lines(values, str::parse)
+    }
+
+    fn part_a(input: &Self::Input) -> Result<Self::Output, Ooops> {
+        Ok(input
+            .iter()
+            .filter(|v| v.a.fully_contains(&v.b) || v.b.fully_contains(&v.a))
+            .count())
+    }
+
+    fn part_b(input: &Self::Input) -> Result<Self::Output, Ooops> {
+        Ok(input
+            .iter()
+            .filter(|v| v.a.overlaps(&v.b) || v.b.overlaps(&v.a))
+            .count())
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use crate::error::ErrorKind;
+
     use super::*;
 
     #[test]
     fn parse_assignment_range() {
         assert_eq!(Ok(AssignmentRange { start: 2, end: 4 }), "2-4".parse());
         assert_eq!(Ok(AssignmentRange { start: 6, end: 8 }), "6-8".parse());
+        let e = "2-x".parse::<AssignmentRange>().unwrap_err();
+        assert_eq!(ErrorKind::Parse("range"), e.kind);
+        assert_eq!("2-x", e.text);
+        assert!(std::error::Error::source(&e).is_some());
     }
 
     #[test]
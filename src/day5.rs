@@ -1,39 +1,94 @@
-use std::{collections::VecDeque, fmt::Display, str::FromStr};
+mod crane;
+mod planner;
+mod reader;
+mod replay;
 
-use crate::error::Ooops;
+use std::{collections::VecDeque, fmt::Display, iter, str::FromStr, sync::OnceLock};
+
+pub use crane::{AdjacentCrane, CountingCrane, Crane, LimitedCrane};
+pub(crate) use planner::plan;
+pub use planner::{Target, PLAN_SEARCH_LIMIT};
+pub(crate) use reader::ActionsReader;
+pub use replay::Replay;
+
+use crate::{
+    error::{ErrorKind, Ooops},
+    solution::{Diagnostics, Solution},
+};
+
+/// Tab stops, every 4 columns like the crates
+const TAB_WIDTH: usize = 4;
 
 #[derive(Debug, PartialEq, Eq)]
 struct Stacks {
+    /// drawing lines, bottom to top, tabs expanded
     lines: Vec<String>,
+    /// middle of every stack number of the footer
+    columns: Vec<usize>,
+    /// next stack to hand out
+    next: usize,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
 struct Crates {
     crates: Vec<String>,
 }
 
+fn expand_tabs(line: &str) -> String {
+    let mut expanded = String::new();
+    for c in line.chars() {
+        match c {
+            '\t' => {
+                expanded.push_str(&" ".repeat(TAB_WIDTH - expanded.chars().count() % TAB_WIDTH))
+            }
+            c => expanded.push(c),
+        }
+    }
+    expanded
+}
+
+/// Every `[label]` of a drawing line, with the columns of its brackets
+fn crates_in(line: &str) -> Vec<(usize, usize, String)> {
+    let mut crates = vec![];
+    let mut opened: Option<(usize, String)> = None;
+    for (column, c) in line.chars().enumerate() {
+        match (c, opened.as_mut()) {
+            ('[', None) => opened = Some((column, String::new())),
+            (']', Some(_)) => {
+                if let Some((start, label)) = opened.take() {
+                    crates.push((start, column, label.trim().to_string()));
+                }
+            }
+            (c, Some((_, label))) => label.push(c),
+            _ => {}
+        }
+    }
+    crates
+}
+
+/// The stack whose number is the closest to `column`
+fn stack_at(columns: &[usize], column: usize) -> Option<usize> {
+    (0..columns.len()).min_by_key(|stack| columns[*stack].abs_diff(column))
+}
+
 impl Iterator for Stacks {
     type Item = Crates;
 
     fn next(&mut self) -> Option<Self::Item> {
+        let stack = self.next;
+        self.columns.get(stack)?;
+        self.next += 1;
         let mut crates = Crates { crates: vec![] };
-        for l in self.lines.iter().filter(|l| !l.trim().is_empty()) {
-            let value = &l[1..2].trim();
-            if value.is_empty() {
-                break;
+        for l in self.lines.iter() {
+            let found = crates_in(l)
+                .into_iter()
+                .find(|(start, end, _)| stack_at(&self.columns, (start + end) / 2) == Some(stack));
+            match found {
+                Some((_, _, label)) => crates.crates.push(label),
+                None => break,
             }
-            crates.crates.push(value.to_string());
-        }
-        self.lines = self
-            .lines
-            .iter()
-            .map(|l| l.chars().skip(4).collect::<String>())
-            .collect();
-        if crates.crates.is_empty() {
-            None
-        } else {
-            Some(crates)
         }
+        Some(crates)
     }
 }
 
@@ -41,17 +96,55 @@ impl FromStr for Stacks {
     type Err = Ooops;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.replace("\r\n", "\n");
         let s = s
             .split("\n\n")
             .next()
-            .ok_or_else(|| Ooops("the first split always has at least one element".to_owned()))?;
+            .ok_or_else(|| Ooops::new(ErrorKind::MissingSection("drawing"), ""))?;
+        let mut lines = s
+            .lines()
+            .enumerate()
+            .filter(|(_, l)| !l.trim().is_empty())
+            .map(|(index, l)| (index + 1, expand_tabs(l)))
+            .collect::<Vec<(usize, String)>>();
+        let footer = lines.pop();
+        lines.reverse();
+        let mut columns = vec![];
+        if let Some((number, footer)) = footer {
+            // the stacks are numbered from 1, in order
+            let numbered = footer
+                .split_whitespace()
+                .enumerate()
+                .all(|(index, n)| n.parse::<usize>() == Ok(index + 1));
+            if !numbered {
+                return Err(Ooops::parse("stack numbers", footer.trim()).at_line(number));
+            }
+            let mut start = None;
+            for (column, c) in footer.chars().chain(iter::once(' ')).enumerate() {
+                match (c.is_whitespace(), start) {
+                    (false, None) => start = Some(column),
+                    (true, Some(first)) => {
+                        columns.push((first + column - 1) / 2);
+                        start = None;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        for (number, line) in lines.iter() {
+            for (start, end, label) in crates_in(line) {
+                // a crate spans the number of its stack
+                let aligned = stack_at(&columns, (start + end) / 2)
+                    .is_some_and(|stack| (start..=end).contains(&columns[stack]));
+                if !aligned {
+                    return Err(Ooops::parse("crate", format!("[{}]", label)).at_line(*number));
+                }
+            }
+        }
         Ok(Self {
-            lines: s
-                .lines()
-                .rev()
-                .skip(1)
-                .map(|s| s.to_owned())
-                .collect::<Vec<String>>(),
+            lines: lines.into_iter().map(|(_, line)| line).collect(),
+            columns,
+            next: 0,
         })
     }
 }
@@ -70,57 +163,129 @@ impl Display for Stacks {
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
-pub(crate) struct CrateAction {
+/// A `move N from A to B` line, stacks being numbered from 1
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct CrateAction {
     quantity: usize,
     from: usize,
     to: usize,
 }
 
+impl CrateAction {
+    /// Moves `quantity` crates from stack `from` to stack `to`
+    pub fn new(quantity: usize, from: usize, to: usize) -> Self {
+        Self { quantity, from, to }
+    }
+
+    /// Number of crates to move
+    pub fn quantity(&self) -> usize {
+        self.quantity
+    }
+
+    /// Source stack
+    pub fn from(&self) -> usize {
+        self.from
+    }
+
+    /// Target stack
+    pub fn to(&self) -> usize {
+        self.to
+    }
+}
+
+/// The puzzle line, `move N from A to B`
+impl Display for CrateAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "move {} from {} to {}",
+            self.quantity, self.from, self.to
+        )
+    }
+}
+
+/// A line written exactly as in the puzzle, without going through the regex
+fn tokenize(line: &str) -> Option<CrateAction> {
+    let mut words = line.split_ascii_whitespace();
+    let mut number = |keyword: &str| -> Option<usize> {
+        if words.next()? != keyword {
+            return None;
+        }
+        let word = words.next()?;
+        match word.bytes().all(|b| b.is_ascii_digit()) {
+            true => word.parse().ok(),
+            false => None,
+        }
+    };
+    let action = CrateAction {
+        quantity: number("move")?,
+        from: number("from")?,
+        to: number("to")?,
+    };
+    words.next().is_none().then_some(action)
+}
+
+static ACTION: OnceLock<regex::Regex> = OnceLock::new();
+
 impl FromStr for CrateAction {
     type Err = Ooops;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let re =
-            regex::Regex::new(r"move (?P<move>\d+) from (?P<from>\d+) to (?P<to>\d+)").unwrap();
-        let result = re
-            .captures_iter(s)
-            .next()
-            .map(|value| Self {
-                quantity: value["move"]
-                    .parse()
-                    .expect("the regex should block this from happening"),
-                from: value["from"]
-                    .parse::<usize>()
-                    .expect("the regex should block this from happening"),
-                to: value["to"]
-                    .parse::<usize>()
-                    .expect("the regex should block this from happening"),
-            })
-            .ok_or_else(|| Ooops(format!("invalid action > '{}'", s)));
-        result
+        if let Some(action) = tokenize(s) {
+            return Ok(action);
+        }
+        let re = ACTION.get_or_init(|| {
+            regex::Regex::new(r"move (?P<move>\d+) from (?P<from>\d+) to (?P<to>\d+)").unwrap()
+        });
+        let value = re.captures(s).ok_or_else(|| Ooops::parse("action", s))?;
+        let number = |name: &str| {
+            value[name]
+                .parse::<usize>()
+                .map_err(|e| Ooops::parse("action", s).caused_by(e))
+        };
+        Ok(Self {
+            quantity: number("move")?,
+            from: number("from")?,
+            to: number("to")?,
+        })
     }
 }
 
 #[derive(Debug, PartialEq, Eq)]
 pub(crate) struct ActionsLines {
     lines: VecDeque<String>,
+    /// line number of the front of `lines`
+    line: usize,
+}
+
+impl ActionsLines {
+    /// Like [`Iterator::next`], but hands out the invalid lines instead of skipping them
+    pub(crate) fn next_checked(&mut self) -> Option<Result<CrateAction, Ooops>> {
+        loop {
+            let line = self.lines.pop_front()?;
+            self.line += 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+            return Some(line.parse().map_err(|e: Ooops| e.at_line(self.line - 1)));
+        }
+    }
 }
 
 impl FromStr for ActionsLines {
     type Err = Ooops;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let s = s
-            .split("\n\n")
-            .nth(1)
-            .ok_or_else(|| Ooops("missing actions in the repo".to_owned()))?;
+        let s = s.replace("\r\n", "\n");
+        let (drawing, actions) = s
+            .split_once("\n\n")
+            .ok_or_else(|| Ooops::new(ErrorKind::MissingSection("actions"), ""))?;
         Ok(Self {
-            lines: s
+            lines: actions
                 .lines()
-                .filter(|l| !l.trim().is_empty())
                 .map(|s| s.to_owned())
                 .collect::<VecDeque<String>>(),
+            line: drawing.lines().count() + 2,
         })
     }
 }
@@ -129,25 +294,22 @@ impl Iterator for ActionsLines {
     type Item = CrateAction;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let mut action = None;
         loop {
-            let maybe_action = self.lines.pop_front().map(|l| l.parse::<CrateAction>());
-            if maybe_action.is_none() {
-                break;
-            }
-            if let Ok(a) = maybe_action.unwrap() {
-                action = Some(a);
-                break;
+            if let Ok(action) = self.next_checked()? {
+                return Some(action);
             }
         }
-        action
     }
 }
 
-pub(crate) struct Warehouse {
+/// Stacks of crates, bottom to top
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct Warehouse {
     stacks: Vec<Crates>,
 }
 
+/// A drawing whose footer numbers the stacks from 1, every crate being drawn over the number of
+/// its stack
 impl FromStr for Warehouse {
     type Err = Ooops;
 
@@ -159,46 +321,205 @@ impl FromStr for Warehouse {
     }
 }
 
-pub(crate) enum CraneType {
+/// The drawing of the puzzle: the crates bottom to top, then the stack numbers, every column
+/// as wide as the widest crate or number
+impl Display for Warehouse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let width = self
+            .stacks
+            .iter()
+            .flat_map(|s| s.crates.iter().map(|label| label.chars().count()))
+            .chain(iter::once(self.stacks.len().to_string().len()))
+            .max()
+            .unwrap_or(1)
+            + 2;
+        let height = self
+            .stacks
+            .iter()
+            .map(|s| s.crates.len())
+            .max()
+            .unwrap_or(0);
+        for level in (0..height).rev() {
+            let line = self
+                .stacks
+                .iter()
+                .map(|s| match s.crates.get(level) {
+                    Some(label) => format!("{:<width$}", format!("[{}]", label)),
+                    None => " ".repeat(width),
+                })
+                .collect::<Vec<String>>();
+            writeln!(f, "{}", line.join(" "))?;
+        }
+        let footer = (1..=self.stacks.len())
+            .map(|number| format!("{:^width$}", number))
+            .collect::<Vec<String>>();
+        write!(f, "{}", footer.join(" "))
+    }
+}
+
+/// How a crane moves several crates at once
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum CraneType {
+    /// One crate at a time, reversing their order (CrateMover 9000)
     Lame,
+    /// All the crates at once, keeping their order (CrateMover 9001)
     Super,
 }
 
+/// What is wrong with a move
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum MoveProblem {
+    /// A stack is numbered 0, while stacks are numbered from 1
+    StackZero,
+    /// A stack is numbered past the last one
+    MissingStack {
+        /// The requested stack
+        stack: usize,
+        /// Number of stacks in the warehouse
+        stacks: usize,
+    },
+    /// The source stack has fewer crates than requested
+    NotEnoughCrates {
+        /// Crates to be moved
+        requested: usize,
+        /// Crates in the source stack
+        height: usize,
+    },
+}
+
+/// A move the crane cannot make
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct MoveError {
+    /// The move number, from 1
+    pub number: usize,
+    /// What is wrong with it
+    pub problem: MoveProblem,
+}
+
+impl Display for MoveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "move {}: ", self.number)?;
+        match self.problem {
+            MoveProblem::StackZero => write!(f, "stacks are numbered from 1"),
+            MoveProblem::MissingStack { stack, stacks } => {
+                write!(f, "no stack {}, there are {} stacks", stack, stacks)
+            }
+            MoveProblem::NotEnoughCrates { requested, height } => write!(
+                f,
+                "cannot take {} crates from a stack of {}",
+                requested, height
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MoveError {}
+
+/// Whether `action` can be made on stacks of the given heights
+fn check(heights: &[usize], action: &CrateAction) -> Result<(), MoveProblem> {
+    for stack in [action.from, action.to] {
+        if stack == 0 {
+            return Err(MoveProblem::StackZero);
+        }
+        if stack > heights.len() {
+            return Err(MoveProblem::MissingStack {
+                stack,
+                stacks: heights.len(),
+            });
+        }
+    }
+    match heights[action.from - 1] {
+        height if height < action.quantity => Err(MoveProblem::NotEnoughCrates {
+            requested: action.quantity,
+            height,
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// `action` as the crane makes it on stacks of the given heights, taking at most the crates of
+/// the source stack, or nothing for a move from or to a missing stack
+fn clamp(heights: &[usize], action: &CrateAction) -> Option<CrateAction> {
+    match check(heights, action) {
+        Err(MoveProblem::StackZero | MoveProblem::MissingStack { .. }) => None,
+        _ => Some(CrateAction {
+            quantity: action.quantity.min(heights[action.from - 1]),
+            ..action.clone()
+        }),
+    }
+}
+
 impl Warehouse {
+    fn heights(&self) -> Vec<usize> {
+        self.stacks.iter().map(|s| s.crates.len()).collect()
+    }
+
+    /// Moves the crates one at a time, as in part A
     pub fn shuffle(&mut self, action: &CrateAction) {
         self.shuffle_with_crane(action, CraneType::Lame)
     }
 
+    /// Moves up to `action.quantity` crates, ignoring the moves from or to missing stacks
     pub fn shuffle_with_crane(&mut self, action: &CrateAction, crane_type: CraneType) {
-        let mut taken = self
-            .stacks
-            .get_mut(action.from - 1)
-            .map_or(vec![], |source| match crane_type {
-                CraneType::Lame => source
-                    .crates
-                    .iter()
-                    .rev()
-                    .take(action.quantity)
-                    .cloned()
-                    .collect::<Vec<String>>(),
-                CraneType::Super => source
-                    .crates
-                    .iter()
-                    .rev()
-                    .take(action.quantity)
-                    .rev()
-                    .cloned()
-                    .collect::<Vec<String>>(),
-            });
-        let taken_number = taken.len();
-        if let Some(target) = self.stacks.get_mut(action.to - 1) {
-            target.crates.append(&mut taken);
+        self.shuffle_with(action, &crane_type);
+    }
+
+    /// Like [`Warehouse::shuffle_with_crane`], for any crane, returning what the move cost
+    pub fn shuffle_with<C: Crane + ?Sized>(&mut self, action: &CrateAction, crane: &C) -> usize {
+        let Some(action) = clamp(&self.heights(), action) else {
+            return 0;
+        };
+        for lift in crane.lifts(&action) {
+            // any crane may be plugged in, its lifts are held to the stacks as they are
+            let Some(lift) = clamp(&self.heights(), &lift) else {
+                continue;
+            };
+            let source = &mut self.stacks[lift.from - 1].crates;
+            let mut taken = source.split_off(source.len() - lift.quantity);
+            self.stacks[lift.to - 1].crates.append(&mut taken);
         }
-        if let Some(source) = self.stacks.get_mut(action.from - 1) {
-            source.crates.truncate(source.crates.len() - taken_number);
+        crane.cost(&action)
+    }
+
+    /// Makes every move with the given crane, returning the total cost
+    pub fn operate<C: Crane + ?Sized>(&mut self, actions: &[CrateAction], crane: &C) -> usize {
+        actions
+            .iter()
+            .map(|action| self.shuffle_with(action, crane))
+            .sum()
+    }
+
+    /// Like [`Warehouse::shuffle_with`], but refuses the moves that cannot be made as written
+    pub fn checked_shuffle<C: Crane + ?Sized>(
+        &mut self,
+        action: &CrateAction,
+        crane: &C,
+    ) -> Result<usize, MoveProblem> {
+        check(&self.heights(), action)?;
+        Ok(self.shuffle_with(action, crane))
+    }
+
+    /// Checks every move without applying any. The invalid ones are then made the way
+    /// [`Warehouse::shuffle_with`] makes them, so that the next ones see the stacks of a real run
+    pub fn validate(&self, actions: &[CrateAction]) -> Vec<MoveError> {
+        let mut heights = self.heights();
+        let mut errors = vec![];
+        for (index, action) in actions.iter().enumerate() {
+            if let Err(problem) = check(&heights, action) {
+                errors.push(MoveError {
+                    number: index + 1,
+                    problem,
+                });
+            }
+            if let Some(action) = clamp(&heights, action) {
+                heights[action.from - 1] -= action.quantity;
+                heights[action.to - 1] += action.quantity;
+            }
         }
+        errors
     }
 
+    /// Label of the top crate of every stack, skipping the empty ones
     pub fn top_crates(&self) -> String {
         self.stacks
             .iter()
@@ -207,16 +528,69 @@ impl Warehouse {
     }
 }
 
+/// Supply Stacks -> <https://adventofcode.com/2022/day/5>
+pub struct Day5;
+
+impl Day5 {
+    fn top_crates_with_crane(
+        (warehouse, actions): &(Warehouse, Vec<CrateAction>),
+        crane_type: CraneType,
+    ) -> String {
+        let mut warehouse = warehouse.clone();
+        for action in actions {
+            warehouse.shuffle_with_crane(action, crane_type);
+        }
+        warehouse.top_crates()
+    }
+}
+
+impl Solution for Day5 {
+    const DAY: usize = 5;
+    type Input = (Warehouse, Vec<CrateAction>);
+    type Output = String;
+
+    fn parse_with(values: &str, diagnostics: &mut Diagnostics) -> Result<Self::Input, Ooops> {
+        let warehouse: Warehouse = values.parse()?;
+        let mut lines: ActionsLines = values.parse()?;
+        let mut actions = vec![];
+        while let Some(action) = lines.next_checked() {
+            match action {
+                Ok(action) => actions.push(action),
+                Err(e) => diagnostics.skip(e)?,
+            }
+        }
+        Ok((warehouse, actions))
+    }
+
+    fn part_a(input: &Self::Input) -> Result<Self::Output, Ooops> {
+        Ok(Self::top_crates_with_crane(input, CraneType::Lame))
+    }
+
+    fn part_b(input: &Self::Input) -> Result<Self::Output, Ooops> {
+        Ok(Self::top_crates_with_crane(input, CraneType::Super))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    pub(crate) const EXAMPLE: &str = "    [D]    
+[N] [C]    
+[Z] [M] [P]
+ 1   2   3 
+
+move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2";
+
     #[test]
     fn parse_actions() {
         let parsed: ActionsLines = crate::input::DAY5.parse().unwrap();
         assert_eq!(
             &"move 2 from 5 to 9".to_string(),
-            parsed.lines.get(0).unwrap()
+            parsed.lines.front().unwrap()
         );
         assert_eq!(
             &"move 3 from 1 to 7".to_string(),
@@ -239,11 +613,40 @@ mod tests {
             "move 2 from 5 to 9".parse().unwrap()
         );
         assert_eq!(
-            Err(Ooops("invalid action > 'banana'".to_string())),
+            Err(Ooops::parse("action", "banana")),
             "banana".parse::<CrateAction>()
         );
     }
 
+    #[test]
+    fn checked_actions() {
+        let mut parsed: ActionsLines =
+            "    [A]\n[B] [C]\n 1   2\n\nmove 1 from 2 to 1\n\nbanana\nmove 1 from 1 to 2"
+                .parse()
+                .unwrap();
+        assert_eq!(
+            Some(Ok(CrateAction {
+                quantity: 1,
+                from: 2,
+                to: 1
+            })),
+            parsed.next_checked()
+        );
+        assert_eq!(
+            Some(Err(Ooops::parse("action", "banana").at_line(7))),
+            parsed.next_checked()
+        );
+        assert_eq!(
+            Some(CrateAction {
+                quantity: 1,
+                from: 1,
+                to: 2
+            }),
+            parsed.next()
+        );
+        assert_eq!(None, parsed.next_checked());
+    }
+
     #[test]
     fn iterate_actions() {
         let mut parsed: ActionsLines = crate::input::DAY5.parse().unwrap();
@@ -294,7 +697,9 @@ mod tests {
                     "[B] [W]     [W] [M] [S] [B]        ".to_string(),
                     "[F] [B]     [C] [S]     [W]        ".to_string(),
                     "[C]         [S] [H]                ".to_string()
-                ]
+                ],
+                columns: vec![1, 5, 9, 13, 17, 21, 25, 29, 33],
+                next: 0
             },
             crate::input::DAY5.parse().unwrap()
         )
@@ -353,6 +758,170 @@ mod tests {
         );
     }
 
+    #[test]
+    fn print_warehouse() {
+        let drawing = crate::input::DAY5.split("\n\n").next().unwrap();
+        let warehouse: Warehouse = crate::input::DAY5.parse().unwrap();
+        assert_eq!(drawing, warehouse.to_string());
+
+        let mut warehouse: Warehouse = "[A]    \n[B] [C]\n 1   2 ".parse().unwrap();
+        warehouse.shuffle(&CrateAction {
+            quantity: 1,
+            from: 2,
+            to: 1,
+        });
+        let drawing = ["[C]    ", "[A]    ", "[B]    ", " 1   2 "].join("\n");
+        assert_eq!(drawing, warehouse.to_string());
+        assert_eq!(Ok(warehouse), drawing.parse());
+    }
+
+    #[test]
+    fn robust_drawing() {
+        let (warehouse, actions) =
+            Day5::parse("[A]    \r\n[B] [C]\r\n 1   2 \r\n\r\nmove 1 from 2 to 1\r\n").unwrap();
+        assert_eq!("AC", warehouse.top_crates());
+        assert_eq!(vec![CrateAction::new(1, 2, 1)], actions);
+
+        let warehouse: Warehouse = "\t    [D]\n\t[N] [C]\n\t 1   2 ".parse().unwrap();
+        assert_eq!("ND", warehouse.top_crates());
+
+        let warehouse = Warehouse {
+            stacks: (1..=11)
+                .map(|n| Crates {
+                    crates: (n..=11).map(|label| format!("c{}", label)).collect(),
+                })
+                .collect(),
+        };
+        let drawing = warehouse.to_string();
+        assert_eq!(
+            Some("  1     2     3     4     5     6     7     8     9    10    11  "),
+            drawing.lines().last()
+        );
+        assert_eq!(
+            Some(format!("{:<65}", "[c11]").as_str()),
+            drawing.lines().next()
+        );
+        assert_eq!(Ok(warehouse), drawing.parse());
+    }
+
+    #[test]
+    fn invalid_drawing() {
+        assert_eq!(
+            Err(Ooops::parse("stack numbers", "hello").at_line(1).in_day(5)),
+            crate::crates_on_top_of_each_stack("hello\n\nmove 1 from 1 to 2")
+        );
+        assert_eq!(
+            Err(Ooops::parse("stack numbers", "1   3").at_line(2)),
+            "[A] [B]\n 1   3 ".parse::<Warehouse>()
+        );
+        assert_eq!(
+            Err(Ooops::parse("crate", "[C]").at_line(2)),
+            "[A]\n[B]         [C]\n 1   2 ".parse::<Warehouse>()
+        );
+        assert_eq!(
+            Err(Ooops::parse("crate", "[C]").at_line(1)),
+            "  [C]\n[A] [B]\n 1   2 ".parse::<Warehouse>()
+        );
+    }
+
+    #[test]
+    fn checked_moves() {
+        let mut warehouse: Warehouse = "[A]    \n[B] [C]\n 1   2 ".parse().unwrap();
+        let action = |quantity, from, to| CrateAction { quantity, from, to };
+        let error = |number, problem| MoveError { number, problem };
+        assert_eq!(
+            Err(MoveProblem::NotEnoughCrates {
+                requested: 3,
+                height: 2
+            }),
+            warehouse.checked_shuffle(&action(3, 1, 2), &CraneType::Lame)
+        );
+        assert_eq!(
+            Err(MoveProblem::StackZero),
+            warehouse.checked_shuffle(&action(1, 0, 2), &CraneType::Lame)
+        );
+        assert_eq!(
+            Err(MoveProblem::MissingStack {
+                stack: 3,
+                stacks: 2
+            }),
+            warehouse.checked_shuffle(&action(1, 1, 3), &CraneType::Super)
+        );
+        assert_eq!("AC", warehouse.top_crates());
+        assert_eq!(
+            Ok(1),
+            warehouse.checked_shuffle(&action(2, 1, 2), &CraneType::Super)
+        );
+        assert_eq!("A", warehouse.top_crates());
+        // unchecked moves from or to missing stacks are ignored, rather than losing crates
+        warehouse.shuffle(&action(1, 2, 3));
+        warehouse.shuffle(&action(1, 0, 1));
+        assert_eq!("A", warehouse.top_crates());
+        assert_eq!(
+            "move 4: cannot take 5 crates from a stack of 3",
+            error(
+                4,
+                MoveProblem::NotEnoughCrates {
+                    requested: 5,
+                    height: 3
+                }
+            )
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn validate_program() {
+        let warehouse: Warehouse = "[A]    \n[B] [C]\n 1   2 ".parse().unwrap();
+        let actions = [
+            CrateAction {
+                quantity: 2,
+                from: 1,
+                to: 2,
+            },
+            CrateAction {
+                quantity: 1,
+                from: 1,
+                to: 2,
+            },
+            CrateAction {
+                quantity: 3,
+                from: 2,
+                to: 1,
+            },
+        ];
+        assert_eq!(
+            vec![MoveError {
+                number: 2,
+                problem: MoveProblem::NotEnoughCrates {
+                    requested: 1,
+                    height: 0
+                }
+            }],
+            warehouse.validate(&actions)
+        );
+        // the first move takes both crates, as the run does, making room for the second one
+        let actions = [CrateAction::new(3, 1, 2), CrateAction::new(3, 2, 1)];
+        assert_eq!(
+            vec![MoveError {
+                number: 1,
+                problem: MoveProblem::NotEnoughCrates {
+                    requested: 3,
+                    height: 2
+                }
+            }],
+            warehouse.validate(&actions)
+        );
+        let (warehouse, actions) = Day5::parse(crate::input::DAY5).unwrap();
+        assert_eq!(Vec::<MoveError>::new(), warehouse.validate(&actions));
+        assert_eq!(
+            Err(Ooops::parse("action", "banana").at_line(5).in_day(5)),
+            crate::validate_crane_actions(
+                "[A]    \n[B] [C]\n 1   2 \n\nbanana\nmove 5 from 1 to 1"
+            )
+        );
+    }
+
     #[test]
     fn move_crates() {
         let mut warehouse = Warehouse {
@@ -369,13 +938,13 @@ mod tests {
             to: 2,
         });
         assert_eq!(warehouse.top_crates(), "a".to_string());
-        assert!(warehouse.stacks.get(0).unwrap().crates.is_empty());
+        assert!(warehouse.stacks.first().unwrap().crates.is_empty());
         warehouse.shuffle(&CrateAction {
             quantity: 10,
             from: 2,
             to: 1,
         });
         assert_eq!(warehouse.top_crates(), "c".to_string());
-        assert!(!warehouse.stacks.get(0).unwrap().crates.is_empty());
+        assert!(!warehouse.stacks.first().unwrap().crates.is_empty());
     }
 }
@@ -0,0 +1,162 @@
+use super::{CraneType, CrateAction};
+
+/// A machine moving crates between stacks. It breaks every move into elementary lifts, each one
+/// taking crates from the top of a stack and putting them down in the same order
+pub trait Crane {
+    /// The lifts making `action`, whose quantity never exceeds the height of the source stack.
+    /// Lifts taking more crates than there are, or reaching missing stacks, are clamped as moves are
+    fn lifts(&self, action: &CrateAction) -> Vec<CrateAction>;
+
+    /// What making `action` costs, one per move unless the crane says otherwise
+    fn cost(&self, _action: &CrateAction) -> usize {
+        1
+    }
+}
+
+impl Crane for CraneType {
+    fn lifts(&self, action: &CrateAction) -> Vec<CrateAction> {
+        match self {
+            CraneType::Lame => vec![CrateAction::new(1, action.from, action.to); action.quantity],
+            CraneType::Super => vec![action.clone()],
+        }
+    }
+}
+
+/// A crane lifting at most that many crates at once (and at least one), splitting the bigger moves
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct LimitedCrane(pub usize);
+
+impl Crane for LimitedCrane {
+    fn lifts(&self, action: &CrateAction) -> Vec<CrateAction> {
+        let capacity = self.0.max(1);
+        let mut lifts = vec![];
+        let mut left = action.quantity;
+        while left > 0 {
+            let quantity = left.min(capacity);
+            lifts.push(CrateAction::new(quantity, action.from, action.to));
+            left -= quantity;
+        }
+        lifts
+    }
+}
+
+/// A crane that only reaches the next stack, going through every stack in between
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct AdjacentCrane<C>(pub C);
+
+impl<C: Crane> Crane for AdjacentCrane<C> {
+    fn lifts(&self, action: &CrateAction) -> Vec<CrateAction> {
+        let stops = |from: usize, to: usize| -> Vec<usize> {
+            match from <= to {
+                true => (from..=to).collect(),
+                false => (to..=from).rev().collect(),
+            }
+        };
+        self.0
+            .lifts(action)
+            .iter()
+            .flat_map(|lift| {
+                let stops = stops(lift.from, lift.to);
+                stops
+                    .windows(2)
+                    .map(|hop| CrateAction::new(lift.quantity, hop[0], hop[1]))
+                    .collect::<Vec<CrateAction>>()
+            })
+            .collect()
+    }
+
+    fn cost(&self, action: &CrateAction) -> usize {
+        self.0.cost(action)
+    }
+}
+
+/// A crane charging one per elementary lift instead of one per move
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct CountingCrane<C>(pub C);
+
+impl<C: Crane> Crane for CountingCrane<C> {
+    fn lifts(&self, action: &CrateAction) -> Vec<CrateAction> {
+        self.0.lifts(action)
+    }
+
+    fn cost(&self, action: &CrateAction) -> usize {
+        self.lifts(action).len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day5::{tests::EXAMPLE, Warehouse};
+
+    use super::*;
+
+    fn run(crane: &impl Crane, actions: &[CrateAction]) -> (String, usize) {
+        let mut warehouse: Warehouse = EXAMPLE.parse().unwrap();
+        let cost = warehouse.operate(actions, crane);
+        (warehouse.top_crates(), cost)
+    }
+
+    #[test]
+    fn cranes() {
+        let actions = [CrateAction::new(3, 2, 1), CrateAction::new(9, 1, 3)];
+        // only 5 crates are left to take in the second move
+        assert_eq!(("Z".to_string(), 2), run(&CraneType::Lame, &actions));
+        assert_eq!(
+            ("Z".to_string(), 8),
+            run(&CountingCrane(CraneType::Lame), &actions)
+        );
+        assert_eq!(("D".to_string(), 2), run(&CraneType::Super, &actions));
+        // lifts of 2, 1, then 2, 2, 1 crates
+        assert_eq!(
+            ("Z".to_string(), 5),
+            run(&CountingCrane(LimitedCrane(2)), &actions)
+        );
+        assert_eq!(
+            ("D".to_string(), 2),
+            run(&AdjacentCrane(CraneType::Super), &actions)
+        );
+        assert_eq!(
+            ("D".to_string(), 3),
+            run(&CountingCrane(AdjacentCrane(CraneType::Super)), &actions)
+        );
+    }
+
+    /// A crane asking for more than there is, and for stacks that do not exist
+    struct WildCrane;
+
+    impl Crane for WildCrane {
+        fn lifts(&self, action: &CrateAction) -> Vec<CrateAction> {
+            vec![
+                CrateAction::new(1, 0, action.to),
+                CrateAction::new(1, action.from, 9),
+                CrateAction::new(100, action.from, action.to),
+            ]
+        }
+    }
+
+    #[test]
+    fn lifts_are_clamped() {
+        // the whole first stack ends up on the third one, nothing else moves
+        assert_eq!(
+            ("DN".to_string(), 1),
+            run(&WildCrane, &[CrateAction::new(1, 1, 3)])
+        );
+    }
+
+    #[test]
+    fn lifts() {
+        assert_eq!(
+            vec![
+                CrateAction::new(2, 3, 2),
+                CrateAction::new(2, 2, 1),
+                CrateAction::new(1, 3, 2),
+                CrateAction::new(1, 2, 1)
+            ],
+            AdjacentCrane(LimitedCrane(2)).lifts(&CrateAction::new(3, 3, 1))
+        );
+        assert_eq!(
+            vec![CrateAction::new(1, 1, 2)],
+            LimitedCrane(0).lifts(&CrateAction::new(1, 1, 2))
+        );
+    }
+}
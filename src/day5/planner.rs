@@ -0,0 +1,245 @@
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+    str::FromStr,
+};
+
+use super::{CraneType, CrateAction, Warehouse};
+use crate::error::{ErrorKind, Inconsistency, Missing, Ooops};
+
+/// How many warehouses a plan looks at before giving up, unless told otherwise. Enough for plans
+/// a few moves deep on small drawings
+pub const PLAN_SEARCH_LIMIT: usize = 200_000;
+
+/// What the rearranged warehouse should look like
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Target {
+    /// The top crate of every stack, as given by [`Warehouse::top_crates`]
+    TopCrates(String),
+    /// Every crate in place
+    Layout(Warehouse),
+}
+
+/// A drawing when there are several lines, the top crates otherwise
+impl FromStr for Target {
+    type Err = Ooops;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim_end().lines().count() {
+            0 | 1 => Ok(Target::TopCrates(s.trim().to_string())),
+            _ => Ok(Target::Layout(s.parse()?)),
+        }
+    }
+}
+
+impl Target {
+    fn reached_by(&self, warehouse: &Warehouse) -> bool {
+        match self {
+            Target::TopCrates(top) => warehouse.top_crates() == *top,
+            Target::Layout(layout) => warehouse == layout,
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Target::TopCrates(top) => top.clone(),
+            Target::Layout(layout) => layout.top_crates(),
+        }
+    }
+}
+
+fn labels(warehouse: &Warehouse) -> Vec<&str> {
+    let mut labels = warehouse
+        .stacks
+        .iter()
+        .flat_map(|s| s.crates.iter().map(|c| c.as_str()))
+        .collect::<Vec<&str>>();
+    labels.sort_unstable();
+    labels
+}
+
+/// Whether a layout can be reached at all, the crane keeping the stacks and the crates
+fn check_layout(start: &Warehouse, layout: &Warehouse) -> Result<(), Ooops> {
+    if start.stacks.len() != layout.stacks.len() {
+        return Err(Ooops::new(
+            ErrorKind::InconsistentState(Inconsistency::StackCount {
+                stacks: layout.stacks.len(),
+                expected: start.stacks.len(),
+            }),
+            format!("{} for {}", layout.stacks.len(), start.stacks.len()),
+        ));
+    }
+    if labels(start) != labels(layout) {
+        return Err(Ooops::new(
+            ErrorKind::InconsistentState(Inconsistency::OtherCrates),
+            labels(layout).concat(),
+        ));
+    }
+    Ok(())
+}
+
+fn fingerprint(warehouse: &Warehouse) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    warehouse.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// The fewest moves rearranging `start` into `target` with the given crane, found with a
+/// breadth-first search over at most `limit` warehouses. Every move multiplies the warehouses
+/// to look at, so that only plans a few moves deep are found in practice
+pub(crate) fn plan(
+    start: &Warehouse,
+    target: &Target,
+    crane: CraneType,
+    limit: usize,
+) -> Result<Vec<CrateAction>, Ooops> {
+    if let Target::Layout(layout) = target {
+        check_layout(start, layout)?;
+    }
+    if target.reached_by(start) {
+        return Ok(vec![]);
+    }
+    // every warehouse seen, with the one it comes from and the move leading to it
+    let mut seen: Vec<(Warehouse, Option<(usize, CrateAction)>)> = vec![(start.clone(), None)];
+    // positions in `seen`, by fingerprint of the warehouse
+    let mut index: HashMap<u64, Vec<usize>> = HashMap::from([(fingerprint(start), vec![0])]);
+    let mut next = 0;
+    let found = 'search: loop {
+        let Some((warehouse, _)) = seen.get(next) else {
+            return Err(Ooops::new(
+                ErrorKind::NotFound(Missing::Plan),
+                target.describe(),
+            ));
+        };
+        let heights = warehouse.heights();
+        let mut moved = vec![];
+        for (from, height) in heights.iter().enumerate() {
+            for to in (0..heights.len()).filter(|to| *to != from) {
+                for quantity in 1..=*height {
+                    let action = CrateAction::new(quantity, from + 1, to + 1);
+                    let mut warehouse = warehouse.clone();
+                    warehouse.shuffle_with_crane(&action, crane);
+                    moved.push((warehouse, action));
+                }
+            }
+        }
+        for (warehouse, action) in moved {
+            let same = index.entry(fingerprint(&warehouse)).or_default();
+            if same.iter().any(|position| seen[*position].0 == warehouse) {
+                continue;
+            }
+            // checked as soon as it is seen, the search being breadth-first
+            let reached = target.reached_by(&warehouse);
+            if !reached && seen.len() == limit {
+                return Err(Ooops::new(
+                    ErrorKind::NotFound(Missing::PlanWithinLimit),
+                    format!("{} warehouses", limit),
+                ));
+            }
+            same.push(seen.len());
+            seen.push((warehouse, Some((next, action))));
+            if reached {
+                break 'search seen.len() - 1;
+            }
+        }
+        next += 1;
+    };
+    let mut actions = vec![];
+    let mut current = found;
+    while let Some((previous, action)) = &seen[current].1 {
+        actions.push(action.clone());
+        current = *previous;
+    }
+    actions.reverse();
+    Ok(actions)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day5::tests::EXAMPLE;
+
+    use super::*;
+
+    fn apply(actions: &[CrateAction], crane: CraneType) -> Warehouse {
+        let mut warehouse: Warehouse = EXAMPLE.parse().unwrap();
+        for action in actions {
+            warehouse.shuffle_with_crane(action, crane);
+        }
+        warehouse
+    }
+
+    #[test]
+    fn plan_top_crates() {
+        let start: Warehouse = EXAMPLE.parse().unwrap();
+        let target: Target = "CMZ".parse().unwrap();
+        // the puzzle needs 4 moves
+        let actions = plan(&start, &target, CraneType::Lame, PLAN_SEARCH_LIMIT).unwrap();
+        assert_eq!(
+            vec!["move 2 from 1 to 3", "move 2 from 2 to 1"],
+            actions
+                .iter()
+                .map(|a| a.to_string())
+                .collect::<Vec<String>>()
+        );
+        assert_eq!("CMZ", apply(&actions, CraneType::Lame).top_crates());
+        assert_eq!(
+            Ok(vec![]),
+            plan(&start, &"NDP".parse().unwrap(), CraneType::Lame, 1)
+        );
+        // the first move tried reaches the target, before looking any further
+        assert_eq!(
+            Ok(vec![CrateAction::new(1, 1, 2)]),
+            plan(&start, &"ZNP".parse().unwrap(), CraneType::Lame, 2)
+        );
+    }
+
+    #[test]
+    fn plan_layout() {
+        let start: Warehouse = EXAMPLE.parse().unwrap();
+        let drawing = "        [Z]\n        [N]\n        [D]\n[C] [M] [P]\n 1   2   3 ";
+        let target: Target = drawing.parse().unwrap();
+        // the lame crane reverses N and Z on its way, the super crane needs a stop over
+        for (crane, moves) in [(CraneType::Lame, 3), (CraneType::Super, 4)] {
+            let actions = plan(&start, &target, crane, PLAN_SEARCH_LIMIT).unwrap();
+            assert_eq!(drawing, apply(&actions, crane).to_string());
+            assert_eq!(moves, actions.len());
+        }
+    }
+
+    #[test]
+    fn impossible_plans() {
+        let start: Warehouse = EXAMPLE.parse().unwrap();
+        let plan = |target: &str, limit| {
+            plan(&start, &target.parse().unwrap(), CraneType::Super, limit).unwrap_err()
+        };
+        assert_eq!(
+            Ooops::new(
+                ErrorKind::InconsistentState(Inconsistency::OtherCrates),
+                "CMNPXZ"
+            ),
+            plan(
+                "[X]    \n[N] [C]\n[Z] [M] [P]\n 1   2   3 ",
+                PLAN_SEARCH_LIMIT
+            )
+        );
+        assert_eq!(
+            ErrorKind::InconsistentState(Inconsistency::StackCount {
+                stacks: 6,
+                expected: 3
+            }),
+            plan(
+                "[N] [C] [D] [Z] [M] [P]\n 1   2   3   4   5   6 ",
+                PLAN_SEARCH_LIMIT
+            )
+            .kind
+        );
+        assert_eq!(
+            Ooops::new(ErrorKind::NotFound(Missing::Plan), "XYZ"),
+            plan("XYZ", PLAN_SEARCH_LIMIT)
+        );
+        assert_eq!(
+            ErrorKind::NotFound(Missing::PlanWithinLimit),
+            plan("XYZ", 10).kind
+        );
+    }
+}
@@ -0,0 +1,113 @@
+use std::io::BufRead;
+
+use super::CrateAction;
+use crate::error::{IoOperation, Ooops};
+
+/// Streams the moves of a puzzle from any reader, one line at a time. The drawing, when the
+/// input starts with one, is skipped, every line after it being a move
+pub(crate) struct ActionsReader<R> {
+    reader: R,
+    buffer: String,
+    /// number of the line in `buffer`
+    line: usize,
+    /// `None` before the first line, then whether the lines read so far belong to a drawing
+    in_drawing: Option<bool>,
+    failed: bool,
+}
+
+/// Whether `line` is drawn crates or the stack numbers below them
+fn is_drawing(line: &str) -> bool {
+    line.contains('[') || line.split_whitespace().all(|n| n.parse::<usize>().is_ok())
+}
+
+impl<R: BufRead> ActionsReader<R> {
+    pub(crate) fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buffer: String::new(),
+            line: 0,
+            in_drawing: None,
+            failed: false,
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for ActionsReader<R> {
+    type Item = Result<CrateAction, Ooops>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.failed {
+            self.buffer.clear();
+            match self.reader.read_line(&mut self.buffer) {
+                Ok(0) => return None,
+                Ok(_) => self.line += 1,
+                Err(e) => {
+                    self.failed = true;
+                    return Some(Err(
+                        Ooops::io(IoOperation::Read, "actions", e).at_line(self.line + 1)
+                    ));
+                }
+            }
+            let line = self.buffer.trim();
+            if line.is_empty() {
+                if self.in_drawing.is_some() {
+                    self.in_drawing = Some(false);
+                }
+                continue;
+            }
+            if self.in_drawing != Some(false) && is_drawing(line) {
+                self.in_drawing = Some(true);
+                continue;
+            }
+            self.in_drawing = Some(false);
+            return Some(line.parse().map_err(|e: Ooops| e.at_line(self.line)));
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day5::ActionsLines;
+
+    use super::*;
+
+    #[test]
+    fn stream_actions() {
+        let read = |input: &str| {
+            ActionsReader::new(input.as_bytes()).collect::<Vec<Result<CrateAction, Ooops>>>()
+        };
+        assert_eq!(
+            vec![
+                Ok(CrateAction::new(1, 2, 1)),
+                Err(Ooops::parse("action", "banana").at_line(6)),
+                Ok(CrateAction::new(1, 1, 2))
+            ],
+            read("    [A]\r\n[B] [C]\r\n 1   2\r\n\r\nmove 1 from 2 to 1\r\nbanana\r\n\r\nmove 1 from 1 to 2")
+        );
+        assert_eq!(
+            vec![Ok(CrateAction::new(3, 1, 2)), Ok(CrateAction::new(4, 5, 6))],
+            read("move 3 from 1 to 2\nnow move 4 from 5 to 6 please\n")
+        );
+        // without a drawing, the first line is a move too
+        assert_eq!(
+            vec![
+                Err(Ooops::parse("action", "mvoe 1 from 2 to 3").at_line(1)),
+                Ok(CrateAction::new(2, 1, 3))
+            ],
+            read("mvoe 1 from 2 to 3\nmove 2 from 1 to 3\n")
+        );
+        assert_eq!(
+            vec![
+                Ok(CrateAction::new(1, 1, 2)),
+                Err(Ooops::parse("action", "[A]").at_line(4))
+            ],
+            read("[A]\n 1   2\nmove 1 from 1 to 2\n[A]")
+        );
+        let actions = ActionsReader::new(crate::input::DAY5.as_bytes())
+            .collect::<Result<Vec<CrateAction>, Ooops>>()
+            .unwrap();
+        let lines: ActionsLines = crate::input::DAY5.parse().unwrap();
+        assert_eq!(lines.collect::<Vec<CrateAction>>(), actions);
+    }
+}
@@ -0,0 +1,165 @@
+use super::{CraneType, CrateAction, Warehouse};
+use crate::error::{ErrorKind, Ooops};
+
+/// Every state of a warehouse while a crane works through its actions, one frame per move
+/// (frame 0 being the initial state), with a cursor that can go back and forth
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Replay {
+    actions: Vec<CrateAction>,
+    crane: CraneType,
+    frames: Vec<Warehouse>,
+    step: usize,
+}
+
+impl Replay {
+    pub(crate) fn record(
+        warehouse: Warehouse,
+        actions: Vec<CrateAction>,
+        crane: CraneType,
+    ) -> Self {
+        let mut frames = vec![warehouse];
+        for action in actions.iter() {
+            let mut next = frames[frames.len() - 1].clone();
+            next.shuffle_with_crane(action, crane);
+            frames.push(next);
+        }
+        Self {
+            actions,
+            crane,
+            frames,
+            step: 0,
+        }
+    }
+
+    /// The crane doing the moves
+    pub fn crane(&self) -> CraneType {
+        self.crane
+    }
+
+    /// Number of moves
+    pub fn len(&self) -> usize {
+        self.actions.len()
+    }
+
+    /// Whether there are no moves at all
+    pub fn is_empty(&self) -> bool {
+        self.actions.is_empty()
+    }
+
+    /// Number of moves applied so far
+    pub fn step(&self) -> usize {
+        self.step
+    }
+
+    /// The warehouse after the moves applied so far
+    pub fn current(&self) -> &Warehouse {
+        &self.frames[self.step]
+    }
+
+    /// The warehouse after `step` moves
+    pub fn snapshot(&self, step: usize) -> Option<&Warehouse> {
+        self.frames.get(step)
+    }
+
+    /// Every state, from the initial one to the one after the last move
+    pub fn snapshots(&self) -> &[Warehouse] {
+        &self.frames
+    }
+
+    /// Applies the next move, returning it, unless every move is already applied
+    pub fn forward(&mut self) -> Option<&CrateAction> {
+        let action = self.actions.get(self.step)?;
+        self.step += 1;
+        Some(action)
+    }
+
+    /// Undoes the last applied move, returning it, unless back at the initial state
+    pub fn back(&mut self) -> Option<&CrateAction> {
+        self.step = self.step.checked_sub(1)?;
+        self.actions.get(self.step)
+    }
+
+    /// Goes to the state after `step` moves
+    pub fn jump(&mut self, step: usize) -> Result<&Warehouse, Ooops> {
+        if step >= self.frames.len() {
+            return Err(Ooops::new(ErrorKind::OutOfRange("move"), step.to_string()));
+        }
+        self.step = step;
+        Ok(self.current())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        day5::{tests::EXAMPLE, Day5},
+        solution::Solution,
+    };
+
+    use super::*;
+
+    fn replay(crane: CraneType) -> Replay {
+        let (warehouse, actions) = Day5::parse(EXAMPLE).unwrap();
+        Replay::record(warehouse, actions, crane)
+    }
+
+    #[test]
+    fn step_by_step() {
+        let mut replay = replay(CraneType::Lame);
+        assert_eq!(4, replay.len());
+        assert_eq!(None, replay.back());
+        assert_eq!("NDP", replay.current().top_crates());
+        assert_eq!(
+            Some(&CrateAction {
+                quantity: 1,
+                from: 2,
+                to: 1
+            }),
+            replay.forward()
+        );
+        assert_eq!("DCP", replay.current().top_crates());
+        replay.forward();
+        assert_eq!(
+            [
+                "        [Z]",
+                "        [N]",
+                "    [C] [D]",
+                "    [M] [P]",
+                " 1   2   3 "
+            ]
+            .join("\n"),
+            replay.current().to_string()
+        );
+        assert!(replay.back().is_some());
+        assert_eq!(1, replay.step());
+        assert_eq!("CMZ", replay.jump(4).unwrap().top_crates());
+        assert_eq!(None, replay.forward());
+        assert_eq!(
+            Err(Ooops::new(ErrorKind::OutOfRange("move"), "5")),
+            replay.jump(5).map(|w| w.top_crates())
+        );
+        assert_eq!(
+            Some("NDP".to_string()),
+            replay.snapshot(0).map(|w| w.top_crates())
+        );
+    }
+
+    #[test]
+    fn both_cranes() {
+        let top = |replay: Replay| {
+            replay
+                .snapshots()
+                .iter()
+                .map(|w| w.top_crates())
+                .collect::<Vec<String>>()
+        };
+        assert_eq!(
+            vec!["NDP", "DCP", "CZ", "MZ", "CMZ"],
+            top(replay(CraneType::Lame))
+        );
+        assert_eq!(
+            vec!["NDP", "DCP", "CD", "CD", "MCD"],
+            top(replay(CraneType::Super))
+        );
+    }
+}
@@ -1,6 +1,12 @@
 use std::collections::HashSet;
 
+use crate::{
+    error::{ErrorKind, Missing, Ooops},
+    solution::{Diagnostics, Solution},
+};
+
 const WINDOW_SIZE: usize = 4;
+const MESSAGE_WINDOW_SIZE: usize = 14;
 
 pub(crate) struct Stream {
     pub(crate) window_size: usize,
@@ -55,6 +61,40 @@ impl Window {
     }
 }
 
+/// Tuning Trouble -> <https://adventofcode.com/2022/day/6>
+pub struct Day6;
+
+impl Day6 {
+    fn marker_position(input: &str, window_size: usize) -> Result<usize, Ooops> {
+        let mut stream: Stream = input.into();
+        stream.window_size = window_size;
+        stream.find_map(|w| w.marker_position()).ok_or_else(|| {
+            Ooops::new(
+                ErrorKind::NotFound(Missing::Marker),
+                window_size.to_string(),
+            )
+        })
+    }
+}
+
+impl Solution for Day6 {
+    const DAY: usize = 6;
+    type Input = String;
+    type Output = usize;
+
+    fn parse_with(values: &str, _: &mut Diagnostics) -> Result<Self::Input, Ooops> {
+        Ok(values.to_string())
+    }
+
+    fn part_a(input: &Self::Input) -> Result<Self::Output, Ooops> {
+        Self::marker_position(input, WINDOW_SIZE)
+    }
+
+    fn part_b(input: &Self::Input) -> Result<Self::Output, Ooops> {
+        Self::marker_position(input, MESSAGE_WINDOW_SIZE)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,6 +1,25 @@
-use std::{cell::RefCell, rc::Rc, str::FromStr};
+mod cleanup;
+mod diff;
+mod disk;
+mod query;
+mod report;
 
-use crate::error::Ooops;
+use std::{collections::HashSet, str::FromStr};
+
+pub(crate) use cleanup::plan;
+pub use cleanup::{Cleanup, CleanupStrategy};
+pub(crate) use diff::diff;
+pub use diff::{FsChange, FsChangeKind, FsDiff};
+pub(crate) use disk::{read_from, write_to};
+pub use query::FsEntry;
+pub(crate) use query::{find_files, largest_files};
+pub use report::DuOrder;
+pub(crate) use report::{du, tree};
+
+use crate::{
+    error::{ErrorKind, Inconsistency, Missing, Ooops},
+    solution::{Diagnostics, Mode, Solution},
+};
 
 #[derive(Debug, PartialEq, Eq)]
 enum Line {
@@ -20,320 +39,570 @@ impl FromStr for Line {
                 Ok(Line::Cd(param.to_string()))
             }
             (Some(prompt), Some(command), None) if prompt == "$" && command == "ls" => Ok(Line::Ls),
-            (Some(dir), Some(name), None) if dir == "dir" => Ok(Line::Dir(name.to_string())),
+            (Some("dir"), Some(name), None) => Ok(Line::Dir(entry_name(name)?)),
             (Some(size), Some(name), None) if size.parse::<usize>().is_ok() => Ok(Line::File {
-                name: name.to_string(),
+                name: entry_name(name)?,
                 size: size.parse::<usize>().unwrap(),
             }),
-            _ => Err(Ooops(format!("invalid line '{}'", s))),
+            _ => Err(Ooops::parse("terminal line", s)),
+        }
+    }
+}
+
+/// A listed name, which must stay inside its directory: not empty, `.`, `..` nor a path
+fn entry_name(name: &str) -> Result<String, Ooops> {
+    match name {
+        "" | "." | ".." => Err(Ooops::parse("file name", name)),
+        _ if name.contains('/') || std::path::Path::new(name).is_absolute() => {
+            Err(Ooops::parse("file name", name))
         }
+        _ => Ok(name.to_string()),
     }
 }
 
-pub(crate) trait FsItem {
-    fn size(&self) -> usize;
+/// Index of a node in a [`FileSystem`]
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Clone, Copy, Default)]
+pub(crate) struct NodeId(usize);
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub(crate) enum FsNodeKind {
+    Directory(Vec<NodeId>),
+    File,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
-pub(crate) struct FsDirectory {
-    name: String,
-    files: Vec<FsFile>,
-    directories: Vec<Rc<RefCell<FsDirectory>>>,
+pub(crate) struct FsNode {
+    pub(crate) name: String,
+    pub(crate) parent: Option<NodeId>,
+    pub(crate) kind: FsNodeKind,
+    /// Own size for files, total size of the content for directories
+    pub(crate) size: usize,
 }
 
-impl FsDirectory {
-    pub(crate) fn ls_directories(&self) -> Vec<Rc<RefCell<FsDirectory>>> {
-        let mut result: Vec<Rc<RefCell<FsDirectory>>> = self.directories.to_vec();
-        for d in self.directories.iter() {
-            result.extend(d.borrow().ls_directories());
+/// Every directory and file, stored side by side. Children always come after their parent
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct FileSystem {
+    nodes: Vec<FsNode>,
+}
+
+impl Default for FileSystem {
+    fn default() -> Self {
+        Self {
+            nodes: vec![FsNode {
+                name: "/".to_string(),
+                parent: None,
+                kind: FsNodeKind::Directory(vec![]),
+                size: 0,
+            }],
         }
-        result
     }
 }
 
-impl FsItem for FsDirectory {
-    fn size(&self) -> usize {
-        let files: usize = self.files.iter().map(|c| c.size).sum();
-        let directories: usize = self.directories.iter().map(|c| c.borrow().size()).sum();
-        files + directories
+impl FileSystem {
+    pub(crate) const ROOT: NodeId = NodeId(0);
+
+    pub(crate) fn node(&self, id: NodeId) -> &FsNode {
+        &self.nodes[id.0]
+    }
+
+    pub(crate) fn size(&self, id: NodeId) -> usize {
+        self.node(id).size
+    }
+
+    pub(crate) fn is_directory(&self, id: NodeId) -> bool {
+        matches!(self.node(id).kind, FsNodeKind::Directory(_))
+    }
+
+    pub(crate) fn children(&self, id: NodeId) -> &[NodeId] {
+        match &self.node(id).kind {
+            FsNodeKind::Directory(children) => children,
+            FsNodeKind::File => &[],
+        }
+    }
+
+    /// The entry of `parent` called `name`
+    pub(crate) fn child(&self, parent: NodeId, name: &str) -> Option<NodeId> {
+        self.children(parent)
+            .iter()
+            .find(|id| self.node(**id).name == name)
+            .copied()
+    }
+
+    /// Absolute path, e.g. `/a/e`
+    pub(crate) fn path(&self, id: NodeId) -> String {
+        let mut names = vec![];
+        let mut current = id;
+        while let Some(parent) = self.node(current).parent {
+            names.push(self.node(current).name.as_str());
+            current = parent;
+        }
+        names.reverse();
+        format!("/{}", names.join("/"))
+    }
+
+    /// The absolute path of `name` inside the directory at `path`
+    pub(crate) fn join(path: &str, name: &str) -> String {
+        match path {
+            "/" => format!("/{}", name),
+            _ => format!("{}/{}", path, name),
+        }
+    }
+
+    /// Number of directories between the root and `id`, the root being at depth 0
+    pub(crate) fn depth(&self, id: NodeId) -> usize {
+        std::iter::successors(self.node(id).parent, |parent| self.node(*parent).parent).count()
+    }
+
+    /// Every directory, the root included
+    pub(crate) fn directories(&self) -> impl Iterator<Item = NodeId> + '_ {
+        (0..self.nodes.len())
+            .map(NodeId)
+            .filter(|id| self.is_directory(*id))
+    }
+
+    fn add(&mut self, parent: NodeId, name: String, kind: FsNodeKind, size: usize) -> NodeId {
+        let id = NodeId(self.nodes.len());
+        self.nodes.push(FsNode {
+            name,
+            parent: Some(parent),
+            kind,
+            size,
+        });
+        if let FsNodeKind::Directory(children) = &mut self.nodes[parent.0].kind {
+            children.push(id);
+        }
+        id
+    }
+
+    pub(crate) fn add_directory(&mut self, parent: NodeId, name: String) -> NodeId {
+        self.add(parent, name, FsNodeKind::Directory(vec![]), 0)
+    }
+
+    pub(crate) fn add_file(&mut self, parent: NodeId, name: String, size: usize) -> NodeId {
+        self.add(parent, name, FsNodeKind::File, size)
+    }
+
+    /// Sets the size of every directory in a single pass: walking backwards, every node is
+    /// complete by the time it is added to its parent
+    pub(crate) fn compute_sizes(&mut self) {
+        for node in self.nodes.iter_mut() {
+            if let FsNodeKind::Directory(_) = node.kind {
+                node.size = 0;
+            }
+        }
+        for index in (1..self.nodes.len()).rev() {
+            let node = &self.nodes[index];
+            if let Some(parent) = node.parent {
+                let size = node.size;
+                self.nodes[parent.0].size += size;
+            }
+        }
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
-struct FsFile {
-    size: usize,
-    name: String,
+/// An `ls` being read: the directory, its line and the entries seen so far
+struct Listing {
+    directory: NodeId,
+    line: usize,
+    repeated: bool,
+    seen: HashSet<NodeId>,
 }
 
-impl FsItem for FsFile {
-    fn size(&self) -> usize {
-        self.size
-    }
+/// Replays a terminal transcript, collecting whatever contradicts the rest of it
+#[derive(Default)]
+struct Replay {
+    fs: FileSystem,
+    current: NodeId,
+    listed: HashSet<NodeId>,
+    listing: Option<Listing>,
+    /// how deep the transcript went below `current` into directories never listed, whose
+    /// entries are ignored
+    unknown: usize,
+    issues: Vec<Ooops>,
 }
 
-pub(crate) fn input_to_root(s: &str) -> Result<FsDirectory, Ooops> {
-    let root = Rc::new(RefCell::new(FsDirectory {
-        name: "/".to_string(),
-        files: vec![],
-        directories: vec![],
-    }));
-    let mut current_path = Vec::from([root.clone()]);
-    for line in s.lines() {
-        match line.parse::<Line>()? {
-            Line::Cd(path) if path == "/" => {
-                current_path = Vec::from([root.clone()]);
+impl Replay {
+    fn issue(&mut self, what: Inconsistency, text: String, line: usize) {
+        self.issues
+            .push(Ooops::new(ErrorKind::InconsistentState(what), text).at_line(line));
+    }
+
+    fn cd(&mut self, name: &str, line: usize) {
+        match self.fs.child(self.current, name) {
+            Some(child) if self.fs.is_directory(child) => {
+                self.current = child;
             }
-            Line::Cd(path) if path == ".." => {
-                if current_path.len() > 1 {
-                    current_path.pop();
+            _ => {
+                self.unknown = 1;
+                let path = FileSystem::join(&self.fs.path(self.current), name);
+                self.issue(Inconsistency::UnknownDirectory, path, line);
+            }
+        }
+    }
+
+    fn ls(&mut self, line: usize) {
+        self.listing = Some(Listing {
+            directory: self.current,
+            line,
+            repeated: !self.listed.insert(self.current),
+            seen: HashSet::new(),
+        });
+    }
+
+    /// Adds an entry to the current directory, unless an earlier listing already has it
+    fn entry(&mut self, name: String, size: Option<usize>, line: usize) {
+        let id = match self.fs.child(self.current, &name) {
+            None => {
+                let id = match size {
+                    Some(size) => self.fs.add_file(self.current, name, size),
+                    None => self.fs.add_directory(self.current, name),
+                };
+                if self.listing.as_ref().is_some_and(|l| l.repeated) {
+                    let path = self.fs.path(id);
+                    self.issue(Inconsistency::MissingFromPreviousListing, path, line);
                 }
+                id
             }
-            Line::Cd(name) => {
-                let current_dir = current_path
-                    .last()
-                    .expect("it should cointain at leaset the root directory")
-                    .clone();
-                let current_dir = current_dir.borrow_mut();
-                let new_path = current_dir
-                    .directories
-                    .iter()
-                    .find(|d| d.borrow().name == name);
-                match new_path {
-                    Some(new_path) => {
-                        current_path.push(new_path.clone());
-                    }
-                    None => {
-                        log::warn!("missign path '{}'", name);
-                    }
+            Some(id) => {
+                let path = self.fs.path(id);
+                match (self.fs.is_directory(id), size) {
+                    (true, None) => {}
+                    (false, Some(size)) if size == self.fs.size(id) => {}
+                    (false, Some(size)) => self.issue(
+                        Inconsistency::FileSize {
+                            before: self.fs.size(id),
+                            after: size,
+                        },
+                        format!("{} ({} then {})", path, self.fs.size(id), size),
+                        line,
+                    ),
+                    _ => self.issue(Inconsistency::FileAndDirectory, path, line),
                 }
+                id
             }
-            Line::Ls => {
-                // no-op
+        };
+        if let Some(listing) = self.listing.as_mut() {
+            listing.seen.insert(id);
+        }
+    }
+
+    /// Reports what an earlier listing of the same directory had but the last one misses
+    fn end_listing(&mut self) {
+        let Some(listing) = self.listing.take() else {
+            return;
+        };
+        if !listing.repeated {
+            return;
+        }
+        for child in self.fs.children(listing.directory).to_vec() {
+            if !listing.seen.contains(&child) {
+                let path = self.fs.path(child);
+                self.issue(Inconsistency::MissingFromNewListing, path, listing.line);
+            }
+        }
+    }
+
+    fn run(
+        mut self,
+        s: &str,
+        diagnostics: &mut Diagnostics,
+    ) -> Result<(FileSystem, Vec<Ooops>), Ooops> {
+        for (index, line) in s.lines().enumerate() {
+            let line_number = index + 1;
+            let line = match line.parse::<Line>() {
+                Ok(line) => line,
+                Err(e) => {
+                    diagnostics.skip(e.at_line(line_number))?;
+                    continue;
+                }
+            };
+            if matches!(line, Line::Cd(_) | Line::Ls) {
+                self.end_listing();
             }
-            Line::Dir(name) => {
-                let current_dir = current_path
-                    .last()
-                    .expect("it should cointain at leaset the root directory");
-                let mut current_dir = current_dir.borrow_mut();
-                let new_dir = Rc::new(RefCell::new(FsDirectory {
-                    name,
-                    files: vec![],
-                    directories: vec![],
-                }));
-                current_dir.directories.push(new_dir);
+            match line {
+                Line::Cd(path) if path == "/" => {
+                    self.current = FileSystem::ROOT;
+                    self.unknown = 0;
+                }
+                Line::Cd(path) if path == ".." && self.unknown > 0 => self.unknown -= 1,
+                Line::Cd(_) if self.unknown > 0 => self.unknown += 1,
+                Line::Ls | Line::Dir(_) | Line::File { .. } if self.unknown > 0 => {}
+                Line::Cd(path) if path == ".." => {
+                    self.current = self
+                        .fs
+                        .node(self.current)
+                        .parent
+                        .unwrap_or(FileSystem::ROOT);
+                }
+                Line::Cd(name) => self.cd(&name, line_number),
+                Line::Ls => self.ls(line_number),
+                Line::Dir(name) => self.entry(name, None, line_number),
+                Line::File { size, name } => self.entry(name, Some(size), line_number),
             }
-            Line::File { size, name } => {
-                let current_dir = current_path
-                    .last_mut()
-                    .expect("it should cointain at leaset the root directory");
-                let mut current_dir = current_dir.borrow_mut();
-                current_dir.files.push(FsFile { name, size });
+        }
+        self.end_listing();
+        for directory in self.fs.directories() {
+            if !self.listed.contains(&directory) {
+                self.issues.push(Ooops::new(
+                    ErrorKind::InconsistentState(Inconsistency::NeverListed),
+                    self.fs.path(directory),
+                ));
             }
         }
+        self.fs.compute_sizes();
+        Ok((self.fs, self.issues))
+    }
+}
+
+/// The filesystem described by a transcript, along with everything inconsistent in it, the lines
+/// that cannot be parsed being reported to `diagnostics`
+pub(crate) fn validate_with(
+    s: &str,
+    diagnostics: &mut Diagnostics,
+) -> Result<(FileSystem, Vec<Ooops>), Ooops> {
+    Replay::default().run(s, diagnostics)
+}
+
+/// Like [`validate_with`], failing on the first line that cannot be parsed
+pub(crate) fn validate(s: &str) -> Result<(FileSystem, Vec<Ooops>), Ooops> {
+    validate_with(s, &mut Diagnostics::new(Mode::Strict))
+}
+
+/// Sum of the sizes of the directories below the root of at most `size`
+pub(crate) fn sum_of_directories_smaller_than(fs: &FileSystem, size: usize) -> usize {
+    fs.directories()
+        .filter(|d| *d != FileSystem::ROOT)
+        .map(|d| fs.size(d))
+        .filter(|d| *d <= size)
+        .sum()
+}
+
+/// Size of the smallest directory freeing enough space, 0 when there already is enough
+pub(crate) fn size_of_the_dir_to_be_deleted(
+    fs: &FileSystem,
+    fs_size: usize,
+    required_free_space: usize,
+) -> Result<usize, Ooops> {
+    match plan(
+        fs,
+        fs_size,
+        required_free_space,
+        CleanupStrategy::SmallestDirectory,
+    ) {
+        Cleanup::NothingToDelete => Ok(0),
+        Cleanup::Delete { freed, .. } => Ok(freed),
+        Cleanup::Impossible { to_free } => Err(Ooops::new(
+            ErrorKind::NotFound(Missing::DirectoryToDelete),
+            to_free.to_string(),
+        )),
+    }
+}
+
+const SMALL_DIRECTORY_SIZE: usize = 100000;
+const FS_SIZE: usize = 70000000;
+const REQUIRED_FREE_SPACE: usize = 30000000;
+
+/// No Space Left On Device -> <https://adventofcode.com/2022/day/7>
+pub struct Day7;
+
+impl Solution for Day7 {
+    const DAY: usize = 7;
+    type Input = FileSystem;
+    type Output = usize;
+
+    fn parse_with(values: &str, diagnostics: &mut Diagnostics) -> Result<Self::Input, Ooops> {
+        validate_with(values, diagnostics).map(|(fs, _)| fs)
+    }
+
+    fn part_a(input: &Self::Input) -> Result<Self::Output, Ooops> {
+        Ok(sum_of_directories_smaller_than(input, SMALL_DIRECTORY_SIZE))
+    }
+
+    fn part_b(input: &Self::Input) -> Result<Self::Output, Ooops> {
+        size_of_the_dir_to_be_deleted(input, FS_SIZE, REQUIRED_FREE_SPACE)
     }
-    let root = root.borrow();
-    Ok(root.clone())
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::solution::Part;
+
     use super::*;
 
-    #[test]
-    fn output_to_items_iteration() {
-        let input = vec![
-            "$ cd /",
-            "$ ls",
-            "dir a",
-            "14848514 b.txt",
-            "8504156 c.dat",
-            "dir d",
-            "$ cd a",
-            "$ ls",
-            "dir e",
-            "29116 f",
-            "2557 g",
-            "62596 h.lst",
-            "$ cd e",
-            "$ ls",
-            "584 i",
-            "$ cd ..",
-            "$ cd ..",
-            "$ cd d",
-            "$ ls",
-            "4060174 j",
-            "8033020 d.log",
-            "5626152 d.ext",
-            "7214296 k",
-        ]
-        .join("\n");
-        let e = FsDirectory {
-            name: "e".to_string(),
-            directories: vec![],
-            files: vec![FsFile {
-                name: "i".to_string(),
-                size: 584,
-            }],
-        };
-        assert_eq!(584, e.size());
-        let a = FsDirectory {
-            name: "a".to_string(),
-            directories: vec![Rc::new(RefCell::new(e))],
-            files: vec![
-                FsFile {
-                    name: "f".to_string(),
-                    size: 29116,
-                },
-                FsFile {
-                    name: "g".to_string(),
-                    size: 2557,
-                },
-                FsFile {
-                    name: "h.lst".to_string(),
-                    size: 62596,
-                },
-            ],
-        };
-        assert_eq!(94853, a.size());
-        let d = FsDirectory {
-            name: "d".to_string(),
-            directories: vec![],
-            files: vec![
-                FsFile {
-                    name: "j".to_string(),
-                    size: 4060174,
-                },
-                FsFile {
-                    name: "d.log".to_string(),
-                    size: 8033020,
-                },
-                FsFile {
-                    name: "d.ext".to_string(),
-                    size: 5626152,
-                },
-                FsFile {
-                    name: "k".to_string(),
-                    size: 7214296,
-                },
-            ],
-        };
-        assert_eq!(24933642, d.size());
-        let root = FsDirectory {
-            name: "/".to_string(),
-            directories: vec![Rc::new(RefCell::new(a)), Rc::new(RefCell::new(d))],
-            files: vec![
-                FsFile {
-                    name: "b.txt".to_string(),
-                    size: 14848514,
-                },
-                FsFile {
-                    name: "c.dat".to_string(),
-                    size: 8504156,
-                },
-            ],
-        };
-        assert_eq!(root, input_to_root(&input).unwrap());
-        assert_eq!(48381165, root.size());
+    pub(crate) const EXAMPLE: &str = "$ cd /
+$ ls
+dir a
+14848514 b.txt
+8504156 c.dat
+dir d
+$ cd a
+$ ls
+dir e
+29116 f
+2557 g
+62596 h.lst
+$ cd e
+$ ls
+584 i
+$ cd ..
+$ cd ..
+$ cd d
+$ ls
+4060174 j
+8033020 d.log
+5626152 d.ext
+7214296 k";
+
+    fn find(fs: &FileSystem, path: &[&str]) -> NodeId {
+        path.iter().fold(FileSystem::ROOT, |id, name| {
+            fs.child(id, name).expect("entry in the example")
+        })
     }
 
     #[test]
-    fn directory_size() {
+    fn output_to_items_iteration() {
+        let fs = validate(EXAMPLE).unwrap().0;
+        assert_eq!(14, fs.nodes.len());
+        let a = find(&fs, &["a"]);
+        let e = find(&fs, &["a", "e"]);
+        assert_eq!(Some(a), fs.node(e).parent);
+        assert_eq!(None, fs.node(FileSystem::ROOT).parent);
+        assert_eq!("/a/e", fs.path(e));
+        assert_eq!("/", fs.path(FileSystem::ROOT));
+        assert_eq!(2, fs.depth(e));
         assert_eq!(
-            0,
-            FsDirectory {
-                name: "/".to_string(),
-                files: vec![],
-                directories: vec![]
-            }
-            .size()
+            vec!["e", "f", "g", "h.lst"],
+            fs.children(a)
+                .iter()
+                .map(|id| fs.node(*id).name.as_str())
+                .collect::<Vec<&str>>()
         );
+        assert!(!fs.is_directory(find(&fs, &["a", "f"])));
+        assert_eq!(584, fs.size(e));
+        assert_eq!(94853, fs.size(a));
+        assert_eq!(24933642, fs.size(find(&fs, &["d"])));
+        assert_eq!(48381165, fs.size(FileSystem::ROOT));
+        assert_eq!(95437, sum_of_directories_smaller_than(&fs, 100000));
+        // the root itself is never counted
+        let only_root = validate("$ cd /\n$ ls\n10 a\n").unwrap().0;
+        assert_eq!(0, sum_of_directories_smaller_than(&only_root, 100000));
         assert_eq!(
-            10,
-            FsDirectory {
-                name: "/".to_string(),
-                files: vec![FsFile {
-                    name: "a".to_string(),
-                    size: 10
-                }],
-                directories: vec![]
-            }
-            .size()
+            Ok(24933642),
+            size_of_the_dir_to_be_deleted(&fs, 70000000, 30000000)
         );
+    }
+
+    #[test]
+    fn directory_size() {
+        let size = |values: &[&str]| {
+            validate(&values.join("\n"))
+                .unwrap()
+                .0
+                .size(FileSystem::ROOT)
+        };
+        assert_eq!(0, size(&["$ cd /", "$ ls"]));
+        assert_eq!(10, size(&["$ ls", "10 a"]));
         assert_eq!(
             20,
-            FsDirectory {
-                name: "/".to_string(),
-                files: vec![FsFile {
-                    name: "a".to_string(),
-                    size: 10
-                }],
-                directories: vec![Rc::new(RefCell::new(FsDirectory {
-                    name: "b".to_string(),
-                    directories: vec![],
-                    files: vec![FsFile {
-                        name: "c".to_string(),
-                        size: 10
-                    }]
-                }))]
-            }
-            .size()
+            size(&["$ ls", "10 a", "dir b", "$ cd b", "$ ls", "10 c"])
         );
         assert_eq!(
             20,
-            FsDirectory {
-                name: "/".to_string(),
-                files: vec![FsFile {
-                    name: "a".to_string(),
-                    size: 10
-                }],
-                directories: vec![Rc::new(RefCell::new(FsDirectory {
-                    name: "b".to_string(),
-                    directories: vec![Rc::new(RefCell::new(FsDirectory {
-                        name: "d".to_string(),
-                        files: vec![],
-                        directories: vec![Rc::new(RefCell::new(FsDirectory {
-                            name: "e".to_string(),
-                            files: vec![],
-                            directories: vec![]
-                        }))]
-                    }))],
-                    files: vec![FsFile {
-                        name: "c".to_string(),
-                        size: 10
-                    }]
-                }))]
-            }
-            .size()
+            size(&[
+                "$ ls", "10 a", "dir b", "$ cd b", "$ ls", "10 c", "dir d", "$ cd d", "$ ls",
+                "dir e"
+            ])
         );
         assert_eq!(
             30,
-            FsDirectory {
-                name: "/".to_string(),
-                files: vec![FsFile {
-                    name: "a".to_string(),
-                    size: 10
-                }],
-                directories: vec![Rc::new(RefCell::new(FsDirectory {
-                    name: "b".to_string(),
-                    directories: vec![Rc::new(RefCell::new(FsDirectory {
-                        name: "d".to_string(),
-                        files: vec![],
-                        directories: vec![Rc::new(RefCell::new(FsDirectory {
-                            name: "e".to_string(),
-                            files: vec![FsFile {
-                                name: "f".to_string(),
-                                size: 10
-                            }],
-                            directories: vec![]
-                        }))]
-                    }))],
-                    files: vec![FsFile {
-                        name: "c".to_string(),
-                        size: 10
-                    }]
-                }))]
-            }
-            .size()
+            size(&[
+                "$ ls", "10 a", "dir b", "$ cd b", "$ ls", "10 c", "dir d", "$ cd d", "$ ls",
+                "dir e", "$ cd e", "$ ls", "10 f"
+            ])
+        );
+    }
+
+    #[test]
+    fn sizes_are_recomputed() {
+        let mut fs = validate(EXAMPLE).unwrap().0;
+        let e = find(&fs, &["a", "e"]);
+        fs.add_file(e, "z".to_string(), 16);
+        fs.compute_sizes();
+        assert_eq!(600, fs.size(e));
+        assert_eq!(94869, fs.size(find(&fs, &["a"])));
+        assert_eq!(48381181, fs.size(FileSystem::ROOT));
+    }
+
+    #[test]
+    fn validate_transcript() {
+        let (fs, issues) = validate(EXAMPLE).unwrap();
+        assert_eq!(Vec::<Ooops>::new(), issues);
+        assert_eq!(validate(EXAMPLE).unwrap().0, fs);
+
+        let values = [
+            "$ cd /", "$ ls", "dir a", "10 b", "dir c", "$ cd x", "$ cd /", "$ ls", "dir a",
+            "20 b", "30 d", "a c",
+        ]
+        .join("\n");
+        let inconsistent = |what, text: &str| Ooops::new(ErrorKind::InconsistentState(what), text);
+        assert_eq!(
+            Err(Ooops::parse("terminal line", "a c").at_line(12)),
+            validate(&values)
+        );
+        let (fs, issues) = validate(&values.replace("a c", "dir b\n$ cd a")).unwrap();
+        assert_eq!(
+            vec![
+                inconsistent(Inconsistency::UnknownDirectory, "/x").at_line(6),
+                inconsistent(
+                    Inconsistency::FileSize {
+                        before: 10,
+                        after: 20
+                    },
+                    "/b (10 then 20)"
+                )
+                .at_line(10),
+                inconsistent(Inconsistency::MissingFromPreviousListing, "/d").at_line(11),
+                inconsistent(Inconsistency::FileAndDirectory, "/b").at_line(12),
+                inconsistent(Inconsistency::MissingFromNewListing, "/c").at_line(8),
+                inconsistent(Inconsistency::NeverListed, "/a"),
+                inconsistent(Inconsistency::NeverListed, "/c"),
+            ],
+            issues
+        );
+        // listing twice doesn't count twice
+        assert_eq!(40, fs.size(FileSystem::ROOT));
+        // what is listed in an unknown directory goes nowhere, up to the way out of it
+        let (fs, issues) = validate(
+            "$ cd /\n$ ls\ndir a\n$ cd x\n$ ls\n10 f\n$ cd y\n$ cd ..\n$ cd ..\n$ ls\n10 g",
+        )
+        .unwrap();
+        assert_eq!(
+            vec![
+                inconsistent(Inconsistency::UnknownDirectory, "/x").at_line(4),
+                inconsistent(Inconsistency::MissingFromPreviousListing, "/g").at_line(11),
+                inconsistent(Inconsistency::MissingFromNewListing, "/a").at_line(10),
+                inconsistent(Inconsistency::NeverListed, "/a"),
+            ],
+            issues
+        );
+        assert_eq!(10, fs.size(FileSystem::ROOT));
+        assert_eq!(
+            Err(Ooops::parse("file name", "..").at_line(3)),
+            validate("$ cd /\n$ ls\ndir ..")
+        );
+    }
+
+    #[test]
+    fn lenient_parsing() {
+        let values = "$ cd /\n$ ls\ndir a\nbanana\n$ cd a\n$ ls\n10 b";
+        let lenient = Day7::solve_with(values, Part::A, Mode::Lenient).unwrap();
+        assert_eq!(
+            vec![Ooops::parse("terminal line", "banana").at_line(4).in_day(7)],
+            lenient.skipped
+        );
+        assert_eq!(
+            Err(Ooops::parse("terminal line", "banana").at_line(4).in_day(7)),
+            Day7::solve_with(values, Part::A, Mode::Strict).map(|d| d.answer)
         );
     }
 
@@ -353,8 +622,18 @@ mod tests {
             "35442 jrfpjdpw.znd".parse::<Line>().unwrap()
         );
         assert_eq!(
-            Err(Ooops("invalid line 'banana'".to_string())),
+            Err(Ooops::parse("terminal line", "banana")),
             "banana".parse::<Line>()
         );
+        for name in [".", "..", "a/b", "/etc"] {
+            assert_eq!(
+                Err(Ooops::parse("file name", name)),
+                format!("10 {}", name).parse::<Line>()
+            );
+            assert_eq!(
+                Err(Ooops::parse("file name", name)),
+                format!("dir {}", name).parse::<Line>()
+            );
+        }
     }
 }
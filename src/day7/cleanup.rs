@@ -0,0 +1,199 @@
+use super::{FileSystem, NodeId};
+
+/// How the directories to delete are chosen
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum CleanupStrategy {
+    /// The smallest single directory freeing enough space, as in the puzzle
+    #[default]
+    SmallestDirectory,
+    /// The fewest directories, none inside another, freeing the least space that is still enough
+    NonNestedSet,
+}
+
+/// Outcome of a cleanup plan
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Cleanup {
+    /// There already is enough free space
+    NothingToDelete,
+    /// Deleting these directories frees `freed`, at least the required space
+    Delete {
+        /// Absolute paths of the directories to delete
+        paths: Vec<String>,
+        /// Total size of the deleted directories
+        freed: usize,
+    },
+    /// The files take more than the whole disk, or no deletion frees `to_free`
+    Impossible {
+        /// Space missing to reach the required free space
+        to_free: usize,
+    },
+}
+
+/// Searches `count` non-nested directories freeing at least `to_free`, but as little as possible
+struct Search<'a> {
+    fs: &'a FileSystem,
+    /// Every deletable directory, biggest first
+    candidates: Vec<NodeId>,
+    to_free: usize,
+    count: usize,
+    picked: Vec<NodeId>,
+    best: Option<(usize, Vec<NodeId>)>,
+}
+
+impl Search<'_> {
+    fn nested(&self, a: NodeId, b: NodeId) -> bool {
+        let contains = |outer: NodeId, inner: NodeId| {
+            std::iter::successors(Some(inner), |id| self.fs.node(*id).parent).any(|id| id == outer)
+        };
+        contains(a, b) || contains(b, a)
+    }
+
+    fn run(&mut self, from: usize, freed: usize) {
+        if self.best.as_ref().is_some_and(|(best, _)| freed >= *best) {
+            return;
+        }
+        if self.picked.len() == self.count {
+            if freed >= self.to_free {
+                self.best = Some((freed, self.picked.clone()));
+            }
+            return;
+        }
+        let missing = self.count - self.picked.len();
+        for index in from..self.candidates.len() {
+            // the candidates are sorted, nothing further can reach the target
+            let reachable: usize = self.candidates[index..]
+                .iter()
+                .take(missing)
+                .map(|id| self.fs.size(*id))
+                .sum();
+            if freed + reachable < self.to_free {
+                return;
+            }
+            let candidate = self.candidates[index];
+            if self.picked.iter().any(|id| self.nested(*id, candidate)) {
+                continue;
+            }
+            self.picked.push(candidate);
+            self.run(index + 1, freed + self.fs.size(candidate));
+            self.picked.pop();
+        }
+    }
+}
+
+/// The fewest non-nested directories freeing `to_free`, and among those the ones freeing the least
+fn non_nested_set(fs: &FileSystem, to_free: usize) -> Option<Vec<NodeId>> {
+    let mut top_level = fs
+        .children(FileSystem::ROOT)
+        .iter()
+        .filter(|id| fs.is_directory(**id))
+        .map(|id| fs.size(*id))
+        .collect::<Vec<usize>>();
+    top_level.sort_by(|a, b| b.cmp(a));
+    // a directory frees at least as much as anything inside of it, so the top level directories
+    // tell how many directories are needed
+    let count = top_level
+        .iter()
+        .scan(0, |freed, size| {
+            *freed += size;
+            Some(*freed)
+        })
+        .position(|freed| freed >= to_free)?
+        + 1;
+    let mut candidates = fs
+        .directories()
+        .filter(|id| *id != FileSystem::ROOT)
+        .collect::<Vec<NodeId>>();
+    candidates.sort_by_key(|id| std::cmp::Reverse(fs.size(*id)));
+    let mut search = Search {
+        fs,
+        candidates,
+        to_free,
+        count,
+        picked: vec![],
+        best: None,
+    };
+    search.run(0, 0);
+    search.best.map(|(_, directories)| directories)
+}
+
+/// What to delete so that `required_free_space` is available on a disk of `fs_size`
+pub(crate) fn plan(
+    fs: &FileSystem,
+    fs_size: usize,
+    required_free_space: usize,
+    strategy: CleanupStrategy,
+) -> Cleanup {
+    let used = fs.size(FileSystem::ROOT);
+    let to_free = (used + required_free_space).saturating_sub(fs_size);
+    if used > fs_size {
+        return Cleanup::Impossible { to_free };
+    }
+    if to_free == 0 {
+        return Cleanup::NothingToDelete;
+    }
+    let chosen = match strategy {
+        CleanupStrategy::SmallestDirectory => fs
+            .directories()
+            .filter(|d| *d != FileSystem::ROOT && fs.size(*d) >= to_free)
+            .min_by_key(|d| fs.size(*d))
+            .map(|d| vec![d]),
+        CleanupStrategy::NonNestedSet => non_nested_set(fs, to_free),
+    };
+    match chosen {
+        Some(directories) => Cleanup::Delete {
+            freed: directories.iter().map(|d| fs.size(*d)).sum(),
+            paths: directories.iter().map(|d| fs.path(*d)).collect(),
+        },
+        None => Cleanup::Impossible { to_free },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day7::{tests::EXAMPLE, validate};
+
+    use super::*;
+
+    #[test]
+    fn smallest_directory() {
+        let fs = validate(EXAMPLE).unwrap().0;
+        let plan = |fs_size, required| plan(&fs, fs_size, required, CleanupStrategy::default());
+        assert_eq!(
+            Cleanup::Delete {
+                paths: vec!["/d".to_string()],
+                freed: 24933642
+            },
+            plan(70000000, 30000000)
+        );
+        assert_eq!(Cleanup::NothingToDelete, plan(100000000, 30000000));
+        assert_eq!(
+            Cleanup::Impossible { to_free: 38381165 },
+            plan(40000000, 30000000)
+        );
+        assert_eq!(
+            Cleanup::Impossible { to_free: 24981165 },
+            plan(70000000, 46600000)
+        );
+    }
+
+    #[test]
+    fn non_nested_set() {
+        let fs = validate(EXAMPLE).unwrap().0;
+        let plan = |required| plan(&fs, 70000000, required, CleanupStrategy::NonNestedSet);
+        assert_eq!(
+            Cleanup::Delete {
+                paths: vec!["/a/e".to_string()],
+                freed: 584
+            },
+            plan(21619419)
+        );
+        assert_eq!(
+            Cleanup::Delete {
+                paths: vec!["/d".to_string(), "/a".to_string()],
+                freed: 25028495
+            },
+            plan(46600000)
+        );
+        assert_eq!(Cleanup::Impossible { to_free: 25081165 }, plan(46700000));
+    }
+}
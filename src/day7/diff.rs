@@ -0,0 +1,204 @@
+use std::fmt::Display;
+
+use super::{FileSystem, NodeId};
+
+/// What happened to an entry between two snapshots
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum FsChangeKind {
+    /// Only in the later snapshot
+    Added,
+    /// Only in the earlier snapshot
+    Removed,
+    /// In both, with different sizes
+    Resized,
+}
+
+/// A file or directory that differs between two snapshots
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct FsChange {
+    /// Absolute path
+    pub path: String,
+    /// What happened
+    pub change: FsChangeKind,
+    /// Whether the entry is a directory
+    pub is_directory: bool,
+    /// Size in the earlier snapshot, 0 when added
+    pub before: usize,
+    /// Size in the later snapshot, 0 when removed
+    pub after: usize,
+}
+
+impl FsChange {
+    /// How much the entry grew (or shrank, when negative)
+    pub fn delta(&self) -> isize {
+        self.after as isize - self.before as isize
+    }
+}
+
+/// Every change between two snapshots, sorted by path. Added and removed directories are
+/// reported once, without their content
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct FsDiff {
+    /// The changes, sorted by path
+    pub changes: Vec<FsChange>,
+    /// How much the whole filesystem grew (or shrank, when negative)
+    pub delta: isize,
+}
+
+struct Snapshots<'a> {
+    before: &'a FileSystem,
+    after: &'a FileSystem,
+    changes: Vec<FsChange>,
+}
+
+impl Snapshots<'_> {
+    fn only_in(&mut self, fs: &FileSystem, id: NodeId, change: FsChangeKind) {
+        let size = fs.size(id);
+        let (before, after) = match change {
+            FsChangeKind::Removed => (size, 0),
+            _ => (0, size),
+        };
+        self.changes.push(FsChange {
+            path: fs.path(id),
+            change,
+            is_directory: fs.is_directory(id),
+            before,
+            after,
+        });
+    }
+
+    fn compare(&mut self, before: NodeId, after: NodeId) {
+        let (old, new) = (self.before, self.after);
+        if old.is_directory(before) != new.is_directory(after) {
+            self.only_in(old, before, FsChangeKind::Removed);
+            self.only_in(new, after, FsChangeKind::Added);
+            return;
+        }
+        if old.size(before) != new.size(after) {
+            self.changes.push(FsChange {
+                path: new.path(after),
+                change: FsChangeKind::Resized,
+                is_directory: new.is_directory(after),
+                before: old.size(before),
+                after: new.size(after),
+            });
+        }
+        for child in old.children(before) {
+            match new.child(after, &old.node(*child).name) {
+                Some(other) => self.compare(*child, other),
+                None => self.only_in(old, *child, FsChangeKind::Removed),
+            }
+        }
+        for child in new.children(after) {
+            if old.child(before, &new.node(*child).name).is_none() {
+                self.only_in(new, *child, FsChangeKind::Added);
+            }
+        }
+    }
+}
+
+/// What changed from `before` to `after`
+pub(crate) fn diff(before: &FileSystem, after: &FileSystem) -> FsDiff {
+    let mut snapshots = Snapshots {
+        before,
+        after,
+        changes: vec![],
+    };
+    snapshots.compare(FileSystem::ROOT, FileSystem::ROOT);
+    let mut changes = snapshots.changes;
+    changes.sort_by(|a, b| a.path.cmp(&b.path));
+    FsDiff {
+        changes,
+        delta: after.size(FileSystem::ROOT) as isize - before.size(FileSystem::ROOT) as isize,
+    }
+}
+
+/// One line per change, `+` added, `-` removed and `~` resized, followed by a summary line
+impl Display for FsDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for change in self.changes.iter() {
+            let kind = match change.is_directory {
+                true => "dir",
+                false => "file",
+            };
+            match change.change {
+                FsChangeKind::Added => {
+                    writeln!(f, "+ {} ({}, size={})", change.path, kind, change.after)?
+                }
+                FsChangeKind::Removed => {
+                    writeln!(f, "- {} ({}, size={})", change.path, kind, change.before)?
+                }
+                FsChangeKind::Resized => writeln!(
+                    f,
+                    "~ {} ({}, size={} -> {}, {:+})",
+                    change.path,
+                    kind,
+                    change.before,
+                    change.after,
+                    change.delta()
+                )?,
+            }
+        }
+        let count = |change| self.changes.iter().filter(|c| c.change == change).count();
+        write!(
+            f,
+            "{} added, {} removed, {} resized, {:+} in total",
+            count(FsChangeKind::Added),
+            count(FsChangeKind::Removed),
+            count(FsChangeKind::Resized),
+            self.delta
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day7::{tests::EXAMPLE, validate};
+
+    use super::*;
+
+    #[test]
+    fn snapshots() {
+        let before = validate(EXAMPLE).unwrap().0;
+        assert_eq!(FsDiff::default(), diff(&before, &before));
+        let after = validate(
+            &EXAMPLE
+                .replace("584 i", "600 i\n10 z")
+                .replace("dir d\n", "dir d\n100 k\n")
+                .replace("8504156 c.dat\n", ""),
+        )
+        .unwrap()
+        .0;
+        let diff = diff(&before, &after);
+        assert_eq!(-8504030, diff.delta);
+        assert_eq!(
+            [
+                "~ / (dir, size=48381165 -> 39877135, -8504030)",
+                "~ /a (dir, size=94853 -> 94879, +26)",
+                "~ /a/e (dir, size=584 -> 610, +26)",
+                "~ /a/e/i (file, size=584 -> 600, +16)",
+                "+ /a/e/z (file, size=10)",
+                "- /c.dat (file, size=8504156)",
+                "+ /k (file, size=100)",
+                "2 added, 1 removed, 4 resized, -8504030 in total",
+            ]
+            .join("\n"),
+            diff.to_string()
+        );
+    }
+
+    #[test]
+    fn file_replaced_by_directory() {
+        let before = validate("$ ls\n10 a").unwrap().0;
+        let after = validate("$ ls\ndir a\n$ cd a\n$ ls\n10 b").unwrap().0;
+        let diff = diff(&before, &after);
+        assert_eq!(
+            vec![(FsChangeKind::Removed, false), (FsChangeKind::Added, true)],
+            diff.changes
+                .iter()
+                .map(|c| (c.change, c.is_directory))
+                .collect::<Vec<(FsChangeKind, bool)>>()
+        );
+        assert_eq!(0, diff.delta);
+    }
+}
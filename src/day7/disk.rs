@@ -0,0 +1,122 @@
+use std::{fs, path::Path};
+
+use super::{FileSystem, NodeId};
+use crate::error::{IoOperation, Ooops};
+
+fn io_error(operation: IoOperation, path: &Path) -> impl Fn(std::io::Error) -> Ooops + '_ {
+    move |e| Ooops::io(operation, path.display(), e)
+}
+
+fn write_directory(fs: &FileSystem, id: NodeId, path: &Path) -> Result<(), Ooops> {
+    fs::create_dir_all(path).map_err(io_error(IoOperation::Write, path))?;
+    for child in fs.children(id) {
+        let node = fs.node(*child);
+        let child_path = path.join(&node.name);
+        match fs.is_directory(*child) {
+            true => write_directory(fs, *child, &child_path)?,
+            false => fs::File::create(&child_path)
+                .and_then(|file| file.set_len(node.size as u64))
+                .map_err(io_error(IoOperation::Write, &child_path))?,
+        }
+    }
+    Ok(())
+}
+
+/// Creates every directory and file under `dir`, the files being sparse ones of the recorded size
+pub(crate) fn write_to(fs: &FileSystem, dir: &Path) -> Result<(), Ooops> {
+    write_directory(fs, FileSystem::ROOT, dir)
+}
+
+fn read_directory(path: &Path, lines: &mut Vec<String>) -> Result<(), Ooops> {
+    let mut entries = fs::read_dir(path)
+        .and_then(|entries| entries.collect::<Result<Vec<fs::DirEntry>, std::io::Error>>())
+        .map_err(io_error(IoOperation::Read, path))?;
+    entries.sort_by_key(|entry| entry.file_name());
+    let mut directories = vec![];
+    lines.push("$ ls".to_string());
+    for entry in entries {
+        let entry_path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.contains(char::is_whitespace) {
+            return Err(Ooops::parse("file name", name));
+        }
+        // symbolic links are not followed
+        let metadata = entry
+            .metadata()
+            .map_err(io_error(IoOperation::Read, &entry_path))?;
+        if metadata.is_dir() {
+            lines.push(format!("dir {}", name));
+            directories.push((name, entry_path));
+        } else if metadata.is_file() {
+            lines.push(format!("{} {}", metadata.len(), name));
+        }
+    }
+    for (name, directory) in directories {
+        lines.push(format!("$ cd {}", name));
+        read_directory(&directory, lines)?;
+        lines.push("$ cd ..".to_string());
+    }
+    Ok(())
+}
+
+/// A `$ cd` / `$ ls` transcript listing everything under `dir`, entries sorted by name
+pub(crate) fn read_from(dir: &Path) -> Result<String, Ooops> {
+    let mut lines = vec!["$ cd /".to_string()];
+    read_directory(dir, &mut lines)?;
+    Ok(lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day7::{tests::EXAMPLE, tree, validate};
+    use crate::error::ErrorKind;
+
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let dir = std::env::temp_dir().join(format!("aoc-day7-{}", std::process::id()));
+        let fs = validate(EXAMPLE).unwrap().0;
+        write_to(&fs, &dir).unwrap();
+        assert_eq!(584, std::fs::metadata(dir.join("a/e/i")).unwrap().len());
+        let transcript = read_from(&dir).unwrap();
+        assert_eq!(
+            [
+                "$ cd /",
+                "$ ls",
+                "dir a",
+                "14848514 b.txt",
+                "8504156 c.dat",
+                "dir d",
+                "$ cd a"
+            ],
+            transcript.lines().take(7).collect::<Vec<&str>>()[..]
+        );
+        let read = validate(&transcript).unwrap().0;
+        assert_eq!(tree(&fs), tree(&read));
+        assert_eq!(fs.size(FileSystem::ROOT), read.size(FileSystem::ROOT));
+
+        std::fs::write(dir.join("a b"), "").unwrap();
+        assert_eq!(Err(Ooops::parse("file name", "a b")), read_from(&dir));
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(
+            ErrorKind::Io(IoOperation::Read),
+            read_from(&dir).unwrap_err().kind
+        );
+    }
+
+    #[test]
+    fn stay_inside_the_directory() {
+        let parent = std::env::temp_dir().join(format!("aoc-day7-escape-{}", std::process::id()));
+        let dir = parent.join("export");
+        std::fs::create_dir_all(&dir).unwrap();
+        assert_eq!(
+            Err(Ooops::parse("file name", "../victim.txt")
+                .at_line(3)
+                .in_day(7)),
+            crate::export_filesystem("$ cd /\n$ ls\n0 ../victim.txt\n", &dir)
+        );
+        assert!(!parent.join("victim.txt").exists());
+        std::fs::remove_dir_all(&parent).unwrap();
+    }
+}
@@ -0,0 +1,173 @@
+use super::{FileSystem, NodeId};
+
+/// A file or directory, as returned by the queries
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct FsEntry {
+    /// Name within its directory, `/` for the root
+    pub name: String,
+    /// Own size for files, total size of the content for directories
+    pub size: usize,
+    /// Whether the entry is a directory
+    pub is_directory: bool,
+}
+
+/// Depth-first walk over every entry, yielding its absolute path
+pub(crate) struct Walk<'a> {
+    fs: &'a FileSystem,
+    stack: Vec<(String, NodeId)>,
+}
+
+impl Iterator for Walk<'_> {
+    type Item = (String, NodeId);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (path, id) = self.stack.pop()?;
+        for child in self.fs.children(id).iter().rev() {
+            let child_path = FileSystem::join(&path, &self.fs.node(*child).name);
+            self.stack.push((child_path, *child));
+        }
+        Some((path, id))
+    }
+}
+
+impl FileSystem {
+    /// The entry at an absolute path such as `/a/e`
+    pub(crate) fn lookup(&self, path: &str) -> Option<NodeId> {
+        path.strip_prefix('/')?
+            .split('/')
+            .filter(|name| !name.is_empty() && *name != ".")
+            .try_fold(FileSystem::ROOT, |id, name| match name {
+                ".." => Some(self.node(id).parent.unwrap_or(FileSystem::ROOT)),
+                name => self.child(id, name),
+            })
+    }
+
+    /// Every entry with its path, each directory followed by its content
+    pub(crate) fn walk(&self) -> Walk<'_> {
+        Walk {
+            fs: self,
+            stack: vec![("/".to_string(), FileSystem::ROOT)],
+        }
+    }
+
+    pub(crate) fn entry(&self, id: NodeId) -> FsEntry {
+        let node = self.node(id);
+        FsEntry {
+            name: node.name.clone(),
+            size: node.size,
+            is_directory: self.is_directory(id),
+        }
+    }
+}
+
+/// Shell-like matching: `*` stands for any run of characters, `?` for a single one
+pub(crate) fn glob(pattern: &str, name: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<char>>();
+    let name = name.chars().collect::<Vec<char>>();
+    let (mut p, mut n) = (0, 0);
+    // where to resume when the text matched by the last `*` has to grow
+    let mut backtrack = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1;
+            }
+            Some(c) if *c == '?' || *c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((star, start)) => {
+                    backtrack = Some((star, start + 1));
+                    p = star + 1;
+                    n = start + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// Files whose name matches `pattern`, in walking order
+pub(crate) fn find_files(fs: &FileSystem, pattern: &str) -> Vec<(String, NodeId)> {
+    fs.walk()
+        .filter(|(_, id)| !fs.is_directory(*id) && glob(pattern, &fs.node(*id).name))
+        .collect()
+}
+
+/// The `n` biggest files, ties by path
+pub(crate) fn largest_files(fs: &FileSystem, n: usize) -> Vec<(String, NodeId)> {
+    let mut files = fs
+        .walk()
+        .filter(|(_, id)| !fs.is_directory(*id))
+        .collect::<Vec<(String, NodeId)>>();
+    files.sort_by(|(a, a_id), (b, b_id)| fs.size(*b_id).cmp(&fs.size(*a_id)).then(a.cmp(b)));
+    files.truncate(n);
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day7::{tests::EXAMPLE, validate};
+
+    use super::*;
+
+    #[test]
+    fn lookup_and_walk() {
+        let fs = validate(EXAMPLE).unwrap().0;
+        let e = fs.lookup("/a/e").unwrap();
+        assert_eq!("e", fs.node(e).name);
+        assert_eq!(Some(e), fs.lookup("/a/e/"));
+        assert_eq!(Some(e), fs.lookup("/d/../a/./e"));
+        assert_eq!(Some(FileSystem::ROOT), fs.lookup("/"));
+        assert_eq!(None, fs.lookup("/a/x"));
+        assert_eq!(None, fs.lookup("a/e"));
+        assert_eq!(None, fs.lookup("/b.txt/x"));
+        assert_eq!(
+            vec!["/", "/a", "/a/e", "/a/e/i", "/a/f", "/a/g", "/a/h.lst", "/b.txt"],
+            fs.walk()
+                .take(8)
+                .map(|(path, _)| path)
+                .collect::<Vec<String>>()
+        );
+        assert_eq!(14, fs.walk().count());
+    }
+
+    #[test]
+    fn globs() {
+        assert!(glob("*.txt", "b.txt"));
+        assert!(glob("d.*", "d.log"));
+        assert!(glob("?", "k"));
+        assert!(glob("*", ""));
+        assert!(glob("a*b*c", "aXbYbZc"));
+        assert!(!glob("*.txt", "b.txt.gz"));
+        assert!(!glob("?", "ab"));
+        assert!(!glob("a*c", "abd"));
+    }
+
+    #[test]
+    fn queries() {
+        let fs = validate(EXAMPLE).unwrap().0;
+        let paths = |found: Vec<(String, NodeId)>| {
+            found
+                .into_iter()
+                .map(|(path, _)| path)
+                .collect::<Vec<String>>()
+        };
+        assert_eq!(vec!["/d/d.log", "/d/d.ext"], paths(find_files(&fs, "d.*")));
+        assert_eq!(
+            vec!["/b.txt", "/c.dat", "/d/d.log"],
+            paths(largest_files(&fs, 3))
+        );
+        assert_eq!(
+            FsEntry {
+                name: "b.txt".to_string(),
+                size: 14848514,
+                is_directory: false
+            },
+            fs.entry(fs.lookup("/b.txt").unwrap())
+        );
+    }
+}
@@ -0,0 +1,113 @@
+use super::{FileSystem, NodeId};
+
+/// How the `du` listing is sorted
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum DuOrder {
+    /// Biggest directories first, ties by path
+    #[default]
+    Size,
+    /// Alphabetically by path
+    Name,
+}
+
+fn sorted_children(fs: &FileSystem, id: NodeId) -> Vec<NodeId> {
+    let mut children = fs.children(id).to_vec();
+    children.sort_by(|a, b| fs.node(*a).name.cmp(&fs.node(*b).name));
+    children
+}
+
+fn tree_lines(fs: &FileSystem, id: NodeId, indent: usize, lines: &mut Vec<String>) {
+    let node = fs.node(id);
+    let padding = "  ".repeat(indent);
+    match fs.is_directory(id) {
+        true => {
+            lines.push(format!("{}- {} (dir)", padding, node.name));
+            for child in sorted_children(fs, id) {
+                tree_lines(fs, child, indent + 1, lines);
+            }
+        }
+        false => lines.push(format!(
+            "{}- {} (file, size={})",
+            padding, node.name, node.size
+        )),
+    }
+}
+
+/// The whole hierarchy as drawn in the puzzle, entries sorted by name
+pub(crate) fn tree(fs: &FileSystem) -> String {
+    let mut lines = vec![];
+    tree_lines(fs, FileSystem::ROOT, 0, &mut lines);
+    lines.join("\n")
+}
+
+/// Path and total size of every directory at most `max_depth` levels below the root
+pub(crate) fn du(
+    fs: &FileSystem,
+    order: DuOrder,
+    max_depth: Option<usize>,
+) -> Vec<(String, usize)> {
+    let mut entries = fs
+        .directories()
+        .filter(|id| max_depth.is_none_or(|max_depth| fs.depth(*id) <= max_depth))
+        .map(|id| (fs.path(id), fs.size(id)))
+        .collect::<Vec<(String, usize)>>();
+    match order {
+        DuOrder::Size => {
+            entries.sort_by(|(a, a_size), (b, b_size)| b_size.cmp(a_size).then(a.cmp(b)))
+        }
+        DuOrder::Name => entries.sort(),
+    }
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day7::{tests::EXAMPLE, validate};
+
+    use super::*;
+
+    #[test]
+    fn puzzle_tree() {
+        let fs = validate(EXAMPLE).unwrap().0;
+        assert_eq!(
+            [
+                "- / (dir)",
+                "  - a (dir)",
+                "    - e (dir)",
+                "      - i (file, size=584)",
+                "    - f (file, size=29116)",
+                "    - g (file, size=2557)",
+                "    - h.lst (file, size=62596)",
+                "  - b.txt (file, size=14848514)",
+                "  - c.dat (file, size=8504156)",
+                "  - d (dir)",
+                "    - d.ext (file, size=5626152)",
+                "    - d.log (file, size=8033020)",
+                "    - j (file, size=4060174)",
+                "    - k (file, size=7214296)",
+            ]
+            .join("\n"),
+            tree(&fs)
+        );
+    }
+
+    #[test]
+    fn disk_usage() {
+        let fs = validate(EXAMPLE).unwrap().0;
+        let entries = |order, max_depth| {
+            du(&fs, order, max_depth)
+                .into_iter()
+                .map(|(path, size)| format!("{} {}", size, path))
+                .collect::<Vec<String>>()
+        };
+        assert_eq!(
+            vec!["48381165 /", "24933642 /d", "94853 /a", "584 /a/e"],
+            entries(DuOrder::Size, None)
+        );
+        assert_eq!(
+            vec!["48381165 /", "94853 /a", "24933642 /d"],
+            entries(DuOrder::Name, Some(1))
+        );
+        assert_eq!(vec!["48381165 /"], entries(DuOrder::Name, Some(0)));
+    }
+}
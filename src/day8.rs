@@ -1,535 +1,255 @@
-#[derive(Debug, PartialEq, Eq, Default, Clone)]
-pub(crate) struct Tree {
-    pub(crate) top: usize,
-    pub(crate) left: usize,
-    pub(crate) height: usize,
-}
+mod export;
+
+use std::{iter, str::FromStr};
+
+pub(crate) use export::{ascii, csv, write_ppm};
+
+use crate::{
+    error::{ErrorKind, Inconsistency, Ooops},
+    grid::{Coord, Direction, Grid},
+    solution::{Diagnostics, Solution},
+};
 
-#[derive(Debug, PartialEq, Eq, Default)]
-pub(crate) struct Trees {
-    field: String,
-    top: usize,
-    left: usize,
+/// Tree heights
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Forest {
+    trees: Grid<u8>,
 }
 
-impl From<&str> for Trees {
-    fn from(s: &str) -> Self {
-        Self {
-            field: s.to_string(),
-            top: 0,
-            left: 0,
-        }
+impl FromStr for Forest {
+    type Err = Ooops;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self {
+            trees: Grid::parse(s, |c| c.to_digit(10).unwrap_or(0) as u8)?,
+        })
     }
 }
 
-impl Iterator for Trees {
-    type Item = Tree;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        loop {
-            if self.field.lines().count() < self.top + 1 {
-                return None;
+impl Forest {
+    /// Every row or column, each one starting at the edge facing `direction`
+    fn lines_from(&self, direction: Direction) -> Vec<Vec<Coord>> {
+        self.trees
+            .edge(direction)
+            .into_iter()
+            .map(|edge| {
+                iter::once(edge)
+                    .chain(
+                        self.trees
+                            .ray(edge, direction.opposite())
+                            .map(|(coord, _)| coord),
+                    )
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Whether each tree can be seen from outside of the forest
+    pub(crate) fn visibility(&self) -> Grid<bool> {
+        let mut visible = self.trees.map(|_| false);
+        for direction in Direction::CARDINAL {
+            for line in self.lines_from(direction) {
+                let mut tallest = None;
+                for coord in line {
+                    let height = self.trees[coord];
+                    if tallest.is_none_or(|tallest| height > tallest) {
+                        visible[coord] = true;
+                        tallest = Some(height);
+                    }
+                }
             }
-            let line = self.field.lines().nth(self.top);
-            if line.is_none() {
-                self.top += 1;
-                continue;
-            }
-            let line = line.expect("just checked this line exists");
-            let height = line.chars().nth(self.left);
-            if height.is_none() {
-                self.left = 0;
-                self.top += 1;
-                continue;
+        }
+        visible
+    }
+
+    /// How many trees each tree sees when looking towards `direction`
+    pub(crate) fn viewing_distances(&self, direction: Direction) -> Grid<usize> {
+        let mut distances = self.trees.map(|_| 0);
+        // walking away from the edge the trees look towards, the view is blocked by the
+        // closest tree already seen that is at least as tall
+        for line in self.lines_from(direction) {
+            // position of the last tree seen for each height
+            let mut last_seen: [Option<usize>; 10] = [None; 10];
+            for (position, coord) in line.into_iter().enumerate() {
+                let height = self.trees[coord] as usize;
+                distances[coord] = match last_seen[height..].iter().flatten().max() {
+                    Some(blocking) => position - blocking,
+                    None => position,
+                };
+                last_seen[height] = Some(position);
             }
-            let height = height.expect("just checked this height exists");
-            let top = self.top;
-            let left = self.left;
-            self.left += 1;
-            return Some(Tree {
-                top,
-                left,
-                height: height.to_digit(10).unwrap_or(0) as usize,
-            });
         }
+        distances
     }
-}
-
-#[derive(Debug, PartialEq, Eq, Default)]
-pub(crate) struct TopTrees {
-    pub(crate) field: String,
-    pub(crate) from_top: usize,
-    pub(crate) from_left: usize,
-}
-
-impl Iterator for TopTrees {
-    type Item = Tree;
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.from_top < 1 {
-            return None;
+    /// Product of the viewing distances in every direction
+    pub(crate) fn scenic_scores(&self) -> Grid<usize> {
+        let mut scores = self.trees.map(|_| 1);
+        for direction in Direction::CARDINAL {
+            for (coord, distance) in self.viewing_distances(direction).iter() {
+                scores[coord] *= distance;
+            }
         }
-        let line = self.field.lines().nth(self.from_top - 1);
-        line?;
-        self.from_top -= 1;
-        let line = line.expect("just checked this line exists");
-        let neighbour_height = line.chars().nth(self.from_left);
-        neighbour_height?;
-        let neighbour_height = neighbour_height.expect("just checked this height exists");
-        let top = self.from_top;
-        Some(Tree {
-            top,
-            left: self.from_left,
-            height: neighbour_height.to_digit(10).unwrap_or(0) as usize,
-        })
+        scores
     }
-}
-
-#[derive(Debug, PartialEq, Eq, Default)]
-pub(crate) struct BottomTrees {
-    pub(crate) field: String,
-    pub(crate) from_top: usize,
-    pub(crate) from_left: usize,
-}
-
-impl Iterator for BottomTrees {
-    type Item = Tree;
 
-    fn next(&mut self) -> Option<Self::Item> {
-        self.from_top += 1;
-        let line = self.field.lines().nth(self.from_top);
-        line?;
-        let line = line.expect("just checked this line exists");
-        let neighbour_height = line.chars().nth(self.from_left);
-        neighbour_height?;
-        let neighbour_height = neighbour_height.expect("just checked this height exists");
-        Some(Tree {
-            top: self.from_top,
-            left: self.from_left,
-            height: neighbour_height.to_digit(10).unwrap_or(0) as usize,
-        })
+    /// Coordinates and scenic score of the tree with the highest score, the topmost-leftmost on ties
+    pub(crate) fn best_spot(&self) -> Option<(Coord, usize)> {
+        self.scenic_scores()
+            .iter()
+            .map(|(coord, score)| (coord, *score))
+            .max_by(|(a, a_score), (b, b_score)| a_score.cmp(b_score).then(b.cmp(a)))
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Default)]
-pub(crate) struct LeftTrees {
-    pub(crate) field: String,
-    pub(crate) from_left: usize,
-    pub(crate) from_top: usize,
-}
+/// Treetop Tree House -> <https://adventofcode.com/2022/day/8>
+pub struct Day8;
 
-impl Iterator for LeftTrees {
-    type Item = Tree;
+impl Solution for Day8 {
+    const DAY: usize = 8;
+    type Input = Forest;
+    type Output = usize;
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.from_left < 1 {
-            return None;
+    fn parse_with(values: &str, diagnostics: &mut Diagnostics) -> Result<Self::Input, Ooops> {
+        let mut width = None;
+        let mut rows = vec![];
+        for (index, line) in values.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            if line.chars().any(|c| !c.is_ascii_digit()) {
+                // invalid trees are kept, with height 0
+                diagnostics.skip(Ooops::parse("tree line", line).at_line(index + 1))?;
+            }
+            let expected = *width.get_or_insert(line.chars().count());
+            if line.chars().count() != expected {
+                // rows of another width than the first one are left out
+                let width = line.chars().count();
+                diagnostics.skip(
+                    Ooops::new(
+                        ErrorKind::InconsistentState(Inconsistency::RowWidth { width, expected }),
+                        line,
+                    )
+                    .at_line(index + 1),
+                )?;
+                continue;
+            }
+            rows.push(line);
         }
-        self.from_left -= 1;
-        let line = self.field.lines().nth(self.from_top);
-        line?;
-        let line = line.expect("just checked this line exists");
-        let neighbour_height = line.chars().nth(self.from_left);
-        neighbour_height?;
-        let neighbour_height = neighbour_height.expect("just checked this height exists");
-        Some(Tree {
-            top: self.from_top,
-            left: self.from_left,
-            height: neighbour_height.to_digit(10).unwrap_or(0) as usize,
-        })
+        rows.join("\n").parse()
     }
-}
 
-#[derive(Debug, PartialEq, Eq, Default)]
-pub(crate) struct RightTrees {
-    pub(crate) field: String,
-    pub(crate) from_left: usize,
-    pub(crate) from_top: usize,
-}
-
-impl Iterator for RightTrees {
-    type Item = Tree;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        let line = self.field.lines().nth(self.from_top);
-        line?;
-        let line = line.expect("just checked this line exists");
-        self.from_left += 1;
-        let neighbour_height = line.chars().nth(self.from_left);
-        neighbour_height?;
-        let neighbour_height = neighbour_height.expect("just checked this height exists");
-        Some(Tree {
-            top: self.from_top,
-            left: self.from_left,
-            height: neighbour_height.to_digit(10).unwrap_or(0) as usize,
-        })
-    }
-}
-
-pub(crate) enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
-fn calculate_viewing_distance<I: Iterator<Item = Tree>>(trees: I, tree: &Tree) -> usize {
-    let mut count = 0;
-    for t in trees {
-        count += 1;
-        if t.height >= tree.height {
-            break;
-        }
+    fn part_a(input: &Self::Input) -> Result<Self::Output, Ooops> {
+        Ok(input.visibility().iter().filter(|(_, v)| **v).count())
     }
-    count
-}
 
-pub(crate) fn viewing_distance(field: String, tree: &Tree, direction: Direction) -> usize {
-    match direction {
-        Direction::Up => calculate_viewing_distance(
-            TopTrees {
-                field,
-                from_left: tree.left,
-                from_top: tree.top,
-            },
-            tree,
-        ),
-        Direction::Down => calculate_viewing_distance(
-            BottomTrees {
-                field,
-                from_left: tree.left,
-                from_top: tree.top,
-            },
-            tree,
-        ),
-        Direction::Left => calculate_viewing_distance(
-            LeftTrees {
-                field,
-                from_left: tree.left,
-                from_top: tree.top,
-            },
-            tree,
-        ),
-        Direction::Right => calculate_viewing_distance(
-            RightTrees {
-                field,
-                from_left: tree.left,
-                from_top: tree.top,
-            },
-            tree,
-        ),
+    fn part_b(input: &Self::Input) -> Result<Self::Output, Ooops> {
+        Ok(input.best_spot().map_or(0, |(_, score)| score))
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::*;
-
-    #[test]
-    fn test_viewing_distance() {
-        let field = vec!["30373", "25512", "65332", "33549", "35390"].join("\n");
-        let tree = Tree {
-            top: 1,
-            left: 2,
-            height: 5,
-        };
-        assert_eq!(1, viewing_distance(field.clone(), &tree, Direction::Up));
-        assert_eq!(1, viewing_distance(field.clone(), &tree, Direction::Left));
-        assert_eq!(2, viewing_distance(field.clone(), &tree, Direction::Right));
-        assert_eq!(2, viewing_distance(field, &tree, Direction::Down));
-    }
+    use crate::solution::{Mode, Part};
 
-    #[test]
-    fn test_viewing_distance_better() {
-        let field = vec!["30373", "25512", "65332", "33549", "35390"].join("\n");
-        let tree = Tree {
-            top: 3,
-            left: 2,
-            height: 5,
-        };
-        assert_eq!(2, viewing_distance(field.clone(), &tree, Direction::Up));
-        assert_eq!(2, viewing_distance(field.clone(), &tree, Direction::Left));
-        assert_eq!(1, viewing_distance(field.clone(), &tree, Direction::Down));
-        assert_eq!(2, viewing_distance(field, &tree, Direction::Right));
-    }
+    use super::*;
 
-    #[test]
-    fn iterate_horizontal_left_trees() {
-        let mut trees = LeftTrees {
-            field: crate::input::DAY8.to_string(),
-            from_top: 1,
-            from_left: 1,
-        };
-        assert_eq!(
-            Some(Tree {
-                height: 3,
-                left: 0,
-                top: 1
-            }),
-            trees.next()
-        );
-        assert_eq!(None, trees.next());
+    pub(crate) fn example() -> Forest {
+        ["30373", "25512", "65332", "33549", "35390"]
+            .join("\n")
+            .parse()
+            .unwrap()
     }
 
     #[test]
-    fn iterate_horizontal_left_trees_two_levels_down() {
-        let mut trees = LeftTrees {
-            field: crate::input::DAY8.to_string(),
-            from_top: 1,
-            from_left: 5,
-        };
-        assert_eq!(
-            Some(Tree {
-                height: 0,
-                left: 4,
-                top: 1
-            }),
-            trees.next()
-        );
-        assert_eq!(
-            Some(Tree {
-                height: 0,
-                left: 3,
-                top: 1
-            }),
-            trees.next()
-        );
-        assert_eq!(
-            Some(Tree {
-                height: 3,
-                left: 2,
-                top: 1
-            }),
-            trees.next()
-        );
-        assert_eq!(
-            Some(Tree {
-                height: 3,
-                left: 1,
-                top: 1
-            }),
-            trees.next()
-        );
-        assert_eq!(
-            Some(Tree {
-                height: 3,
-                left: 0,
-                top: 1
-            }),
-            trees.next()
-        );
-        assert_eq!(None, trees.next());
-    }
-
-    #[test]
-    fn iterate_horizontal_right_trees() {
-        let mut trees = RightTrees {
-            field: crate::input::DAY8.to_string(),
-            from_top: 1,
-            from_left: 1,
-        };
-        assert_eq!(
-            Some(Tree {
-                height: 3,
-                left: 2,
-                top: 1
-            }),
-            trees.next()
-        );
-        assert_eq!(
-            Some(Tree {
-                height: 0,
-                left: 3,
-                top: 1
-            }),
-            trees.next()
-        );
-        assert_eq!(
-            Some(Tree {
-                height: 0,
-                left: 4,
-                top: 1
-            }),
-            trees.next()
-        );
-        assert_eq!(
-            Some(Tree {
-                height: 1,
-                left: 5,
-                top: 1
-            }),
-            trees.next()
-        );
+    fn test_viewing_distance() {
+        let forest = example();
+        let tree = Coord::new(1, 2);
+        assert_eq!(1, forest.viewing_distances(Direction::Up)[tree]);
+        assert_eq!(1, forest.viewing_distances(Direction::Left)[tree]);
+        assert_eq!(2, forest.viewing_distances(Direction::Right)[tree]);
+        assert_eq!(2, forest.viewing_distances(Direction::Down)[tree]);
     }
 
     #[test]
-    fn iterate_vertical_top_trees() {
-        let mut vertical_trees = TopTrees {
-            field: crate::input::DAY8.to_string(),
-            from_left: 1,
-            from_top: 1,
-        };
-        assert_eq!(
-            Some(Tree {
-                height: 2,
-                left: 1,
-                top: 0
-            }),
-            vertical_trees.next()
-        );
-        assert_eq!(None, vertical_trees.next());
+    fn test_viewing_distance_better() {
+        let forest = example();
+        let tree = Coord::new(3, 2);
+        assert_eq!(2, forest.viewing_distances(Direction::Up)[tree]);
+        assert_eq!(2, forest.viewing_distances(Direction::Left)[tree]);
+        assert_eq!(1, forest.viewing_distances(Direction::Down)[tree]);
+        assert_eq!(2, forest.viewing_distances(Direction::Right)[tree]);
     }
 
     #[test]
-    fn iterate_vertical_top_trees_two_levels_down() {
-        let mut vertical_trees = TopTrees {
-            field: crate::input::DAY8.to_string(),
-            from_left: 1,
-            from_top: 4,
-        };
-        assert_eq!(
-            Some(Tree {
-                height: 0,
-                left: 1,
-                top: 3
-            }),
-            vertical_trees.next()
-        );
-        assert_eq!(
-            Some(Tree {
-                height: 0,
-                left: 1,
-                top: 2
-            }),
-            vertical_trees.next()
-        );
-        assert_eq!(
-            Some(Tree {
-                height: 3,
-                left: 1,
-                top: 1
-            }),
-            vertical_trees.next()
-        );
-        assert_eq!(
-            Some(Tree {
-                height: 2,
-                left: 1,
-                top: 0
-            }),
-            vertical_trees.next()
-        );
-        assert_eq!(None, vertical_trees.next());
+    fn visibility_and_scenic_scores() {
+        let forest = example();
+        let visible = forest.visibility();
+        assert_eq!(21, visible.iter().filter(|(_, v)| **v).count());
+        assert!(!visible[Coord::new(1, 3)]);
+        let scores = forest.scenic_scores();
+        assert_eq!(8, scores[Coord::new(3, 2)]);
+        assert_eq!(Some((Coord::new(3, 2), 8)), forest.best_spot());
+        assert_eq!(None, Forest::default().best_spot());
     }
 
     #[test]
-    fn iterate_vertical_bottom_trees() {
-        let mut vertical_trees = BottomTrees {
-            field: crate::input::DAY8.to_string(),
-            from_left: 1,
-            from_top: 1,
-        };
+    fn lines_from_each_edge() {
+        let forest: Forest = crate::input::DAY8.parse().unwrap();
+        let heights = |line: &[Coord]| line.iter().map(|c| forest.trees[*c]).collect::<Vec<u8>>();
+        let rows = forest.lines_from(Direction::Left);
+        assert_eq!(vec![3, 3, 3, 0, 0, 1], heights(&rows[1][..6]));
+        let columns = forest.lines_from(Direction::Up);
+        assert_eq!(vec![2, 3, 0, 0, 0, 2], heights(&columns[1][..6]));
         assert_eq!(
-            Some(Tree {
-                height: 0,
-                left: 1,
-                top: 2
-            }),
-            vertical_trees.next()
-        );
-        assert_eq!(
-            Some(Tree {
-                height: 0,
-                left: 1,
-                top: 3
-            }),
-            vertical_trees.next()
+            Coord::new(1, forest.trees.width() - 1),
+            forest.lines_from(Direction::Right)[1][0]
         );
         assert_eq!(
-            Some(Tree {
-                height: 0,
-                left: 1,
-                top: 4
-            }),
-            vertical_trees.next()
-        );
-        assert_eq!(
-            Some(Tree {
-                height: 2,
-                left: 1,
-                top: 5
-            }),
-            vertical_trees.next()
+            Coord::new(forest.trees.height() - 1, 1),
+            forest.lines_from(Direction::Down)[1][0]
         );
     }
 
     #[test]
-    fn iterate_trees() {
-        let mut trees: Trees = "0233\n4177".into();
+    fn parse_forest() {
         assert_eq!(
-            Some(Tree {
-                height: 0,
-                left: 0,
-                top: 0
+            Ok(Forest {
+                trees: Grid::new(4, 2, vec![0, 2, 3, 3, 4, 1, 7, 7]).unwrap()
             }),
-            trees.next()
+            "0233\n4177\n".parse()
         );
         assert_eq!(
-            Some(Tree {
-                height: 2,
-                left: 1,
-                top: 0
-            }),
-            trees.next()
-        );
-        assert_eq!(
-            Some(Tree {
-                height: 3,
-                left: 2,
-                top: 0
-            }),
-            trees.next()
+            Err(Ooops::new(
+                ErrorKind::InconsistentState(Inconsistency::RowWidth {
+                    width: 3,
+                    expected: 4
+                }),
+                "417"
+            )
+            .at_line(2)),
+            "0233\n417".parse::<Forest>()
         );
+        let ragged = "123\n12\n123";
+        let lenient = Day8::solve_with(ragged, Part::A, Mode::Lenient).unwrap();
+        assert_eq!(6, lenient.answer);
         assert_eq!(
-            Some(Tree {
-                height: 3,
-                left: 3,
-                top: 0
-            }),
-            trees.next()
-        );
-        assert_eq!(
-            Some(Tree {
-                height: 4,
-                left: 0,
-                top: 1
-            }),
-            trees.next()
+            vec![Ooops::new(
+                ErrorKind::InconsistentState(Inconsistency::RowWidth {
+                    width: 2,
+                    expected: 3
+                }),
+                "12"
+            )
+            .at_line(2)
+            .in_day(8)],
+            lenient.skipped
         );
         assert_eq!(
-            Some(Tree {
-                height: 1,
-                left: 1,
-                top: 1
-            }),
-            trees.next()
-        );
-        assert_eq!(
-            Some(Tree {
-                height: 7,
-                left: 2,
-                top: 1
-            }),
-            trees.next()
-        );
-        assert_eq!(
-            Some(Tree {
-                height: 7,
-                left: 3,
-                top: 1
-            }),
-            trees.next()
+            Err(lenient.skipped[0].clone()),
+            Day8::solve_with(ragged, Part::A, Mode::Strict).map(|d| d.answer)
         );
-        assert_eq!(None, trees.next());
+        assert_eq!(6, crate::trees_visible_from_outside_the_grid(ragged));
     }
 }
@@ -0,0 +1,97 @@
+use std::io::Write;
+
+use crate::grid::Grid;
+
+/// `#` for the visible trees, `.` for the hidden ones
+pub(crate) fn ascii(visible: &Grid<bool>) -> String {
+    visible
+        .map(|visible| match visible {
+            true => '#',
+            false => '.',
+        })
+        .to_string()
+}
+
+/// One line per row of comma separated values
+pub(crate) fn csv(scores: &Grid<usize>) -> String {
+    (0..scores.height())
+        .filter_map(|top| scores.row(top))
+        .map(|row| {
+            row.iter()
+                .map(|score| score.to_string())
+                .collect::<Vec<String>>()
+                .join(",")
+        })
+        .map(|line| line + "\n")
+        .collect()
+}
+
+/// Black (lowest) to red to yellow (highest). The scale is logarithmic, as a few trees have
+/// scores orders of magnitude above the rest
+fn heat(score: usize, max: usize) -> [u8; 3] {
+    let t = match max {
+        0 => 0.0,
+        max => (score as f64).ln_1p() / (max as f64).ln_1p(),
+    };
+    [
+        (255.0 * (2.0 * t).min(1.0)) as u8,
+        (255.0 * (2.0 * t - 1.0).max(0.0)) as u8,
+        0,
+    ]
+}
+
+/// Binary PPM heatmap, one pixel per tree
+pub(crate) fn write_ppm(scores: &Grid<usize>, out: &mut impl Write) -> std::io::Result<()> {
+    write!(out, "P6\n{} {}\n255\n", scores.width(), scores.height())?;
+    let max = scores.iter().map(|(_, score)| *score).max().unwrap_or(0);
+    let pixels = scores
+        .iter()
+        .flat_map(|(_, score)| heat(*score, max))
+        .collect::<Vec<u8>>();
+    out.write_all(&pixels)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day8::tests::example;
+    use crate::error::{ErrorKind, IoOperation};
+
+    use super::*;
+
+    #[test]
+    fn ascii_and_csv() {
+        let forest = example();
+        assert_eq!(
+            ["#####", "###.#", "##.##", "#.#.#", "#####"].join("\n"),
+            ascii(&forest.visibility())
+        );
+        assert_eq!(
+            "0,0,0,0,0\n0,1,4,1,0\n0,6,1,2,0\n0,1,8,3,0\n0,0,0,0,0\n",
+            csv(&forest.scenic_scores())
+        );
+    }
+
+    #[test]
+    fn ppm() {
+        let mut image = vec![];
+        write_ppm(&example().scenic_scores(), &mut image).unwrap();
+        let header = b"P6\n5 5\n255\n";
+        assert_eq!(header, &image[..header.len()]);
+        let pixels = &image[header.len()..];
+        assert_eq!(75, pixels.len());
+        assert_eq!([0, 0, 0], pixels[..3]);
+        // the best tree, at (3, 2)
+        assert_eq!([255, 255, 0], pixels[3 * 17..3 * 17 + 3]);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn failed_export() {
+        // the image fits in the buffer, only flushing it fails
+        let e = crate::export_scenic_heatmap("30373\n25512", "/dev/full").unwrap_err();
+        assert_eq!(
+            (ErrorKind::Io(IoOperation::Write), Some(8)),
+            (e.kind, e.day)
+        );
+    }
+}
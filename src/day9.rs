@@ -1,6 +1,13 @@
-use std::str::FromStr;
+mod render;
 
-use crate::error::Ooops;
+use std::{collections::HashSet, str::FromStr};
+
+pub(crate) use render::RopeHistory;
+
+use crate::{
+    error::{ErrorKind, Ooops},
+    solution::{Diagnostics, Solution},
+};
 
 #[derive(Debug, PartialEq, Eq, Default, Clone, Hash)]
 pub(crate) struct Position {
@@ -8,14 +15,51 @@ pub(crate) struct Position {
     left: isize,
 }
 
-#[derive(Debug, PartialEq, Eq)]
-pub(crate) enum Movement {
+impl Position {
+    fn touches(&self, other: &Position) -> bool {
+        (self.top - other.top).abs() <= 1 && (self.left - other.left).abs() <= 1
+    }
+
+    /// Unless it already touches `leader`, steps one unit towards it (diagonally when not on the same row or column)
+    pub(crate) fn follow(&mut self, leader: &Position) {
+        if self.touches(leader) {
+            return;
+        }
+        self.top += (leader.top - self.top).signum();
+        self.left += (leader.left - self.left).signum();
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Movement {
     Up(u32),
     Down(u32),
     Left(u32),
     Right(u32),
 }
 
+impl Movement {
+    /// Number of steps and the (top, left) offset of each one
+    fn steps(&self) -> (u32, isize, isize) {
+        match self {
+            Movement::Up(steps) => (*steps, 1, 0),
+            Movement::Down(steps) => (*steps, -1, 0),
+            Movement::Left(steps) => (*steps, 0, -1),
+            Movement::Right(steps) => (*steps, 0, 1),
+        }
+    }
+
+    /// The same movement, one step long
+    fn unit(&self) -> Movement {
+        match self {
+            Movement::Up(_) => Movement::Up(1),
+            Movement::Down(_) => Movement::Down(1),
+            Movement::Left(_) => Movement::Left(1),
+            Movement::Right(_) => Movement::Right(1),
+        }
+    }
+}
+
 impl FromStr for Movement {
     type Err = Ooops;
 
@@ -34,7 +78,7 @@ impl FromStr for Movement {
             (Some(direction), Some(value)) if direction == "R" && value.parse::<u32>().is_ok() => {
                 Ok(Movement::Right(value.parse::<u32>().unwrap()))
             }
-            _ => Err(Ooops(format!("invalid movement '{}'", s))),
+            _ => Err(Ooops::parse("movement", s)),
         }
     }
 }
@@ -42,76 +86,163 @@ impl FromStr for Movement {
 #[derive(Debug, PartialEq, Eq)]
 pub(crate) struct Rope {
     head: Position,
+    /// knots following the head, the last one being the tail
+    knots: Vec<Position>,
+    /// positions visited by the tail
     pub(crate) tail: Vec<Position>,
 }
 
-impl Default for Rope {
-    fn default() -> Self {
+impl Rope {
+    /// A rope with `knots` knots (head included), all of them at the origin
+    pub(crate) fn with_knots(knots: usize) -> Self {
         Self {
             head: Default::default(),
+            knots: vec![Default::default(); knots.max(1) - 1],
             tail: vec![Default::default()],
         }
     }
 }
 
+impl Default for Rope {
+    fn default() -> Self {
+        Self::with_knots(2)
+    }
+}
+
 pub(crate) fn move_head(mut rope: Rope, movement: Movement) -> Rope {
-    match movement {
-        Movement::Up(steps) => {
-            for _ in 0..steps {
-                rope.head.top += 1;
-                let tail = rope.tail.last().expect("this list starts with one element");
-                if rope.head.top - tail.top > 1 {
-                    rope.tail.push(Position {
-                        left: rope.head.left,
-                        top: rope.head.top - 1,
-                    });
-                }
-            }
+    let (steps, top, left) = movement.steps();
+    for _ in 0..steps {
+        rope.head.top += top;
+        rope.head.left += left;
+        let mut leader = &rope.head;
+        for knot in rope.knots.iter_mut() {
+            knot.follow(leader);
+            leader = knot;
         }
-        Movement::Down(steps) => {
-            for _ in 0..steps {
-                rope.head.top -= 1;
-                let tail = rope.tail.last().expect("this list starts with one element");
-                if tail.top - rope.head.top > 1 {
-                    rope.tail.push(Position {
-                        left: rope.head.left,
-                        top: rope.head.top + 1,
-                    });
-                }
-            }
-        }
-        Movement::Left(steps) => {
-            for _ in 0..steps {
-                rope.head.left -= 1;
-                let tail = rope.tail.last().expect("this list starts with one element");
-                if tail.left - rope.head.left > 1 {
-                    rope.tail.push(Position {
-                        left: rope.head.left + 1,
-                        top: rope.head.top,
-                    });
-                }
-            }
-        }
-        Movement::Right(steps) => {
-            for _ in 0..steps {
-                rope.head.left += 1;
-                let tail = rope.tail.last().expect("this list starts with one element");
-                if rope.head.left - tail.left > 1 {
-                    rope.tail.push(Position {
-                        left: rope.head.left - 1,
-                        top: rope.head.top,
-                    });
-                }
-            }
+        if rope.tail.last() != Some(leader) {
+            rope.tail.push(leader.clone());
         }
     }
     rope
 }
 
+/// Fails for a rope without any knot, the head being one
+pub(crate) fn check_knots(knots: usize) -> Result<(), Ooops> {
+    match knots {
+        0 => Err(Ooops::new(ErrorKind::OutOfRange("knots"), "0")),
+        _ => Ok(()),
+    }
+}
+
+/// Number of positions visited by the tail of a rope with `knots` knots
+pub(crate) fn tail_visits(movements: &[Movement], knots: usize) -> usize {
+    let mut rope = Rope::with_knots(knots);
+    for movement in movements {
+        rope = move_head(rope, movement.clone());
+    }
+    let tail_positions: HashSet<Position> = HashSet::from_iter(rope.tail.iter().cloned());
+    tail_positions.len()
+}
+
+/// Rope Bridge -> <https://adventofcode.com/2022/day/9>
+pub struct Day9;
+
+impl Solution for Day9 {
+    const DAY: usize = 9;
+    type Input = Vec<Movement>;
+    type Output = usize;
+
+    fn parse_with(values: &str, diagnostics: &mut Diagnostics) -> Result<Self::Input, Ooops> {
+        diagnostics.parse_lines(values, |line| line.parse())
+    }
+
+    fn part_a(input: &Self::Input) -> Result<Self::Output, Ooops> {
+        Ok(tail_visits(input, 2))
+    }
+
+    fn part_b(input: &Self::Input) -> Result<Self::Output, Ooops> {
+        Ok(tail_visits(input, 10))
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
+    use crate::solution::{Mode, Part};
+
     use super::*;
 
+    /// Brute-force `follow`: the neighbouring cell closest to the leader, unless already touching
+    fn reference_follow(knot: &Position, leader: &Position) -> Position {
+        if (knot.top - leader.top).abs() <= 1 && (knot.left - leader.left).abs() <= 1 {
+            return knot.clone();
+        }
+        let mut candidates = vec![];
+        for top in -1..=1 {
+            for left in -1..=1 {
+                candidates.push(Position {
+                    top: knot.top + top,
+                    left: knot.left + left,
+                });
+            }
+        }
+        candidates
+            .into_iter()
+            .min_by_key(|p| (p.top - leader.top).pow(2) + (p.left - leader.left).pow(2))
+            .expect("there are always nine candidates")
+    }
+
+    fn position() -> impl Strategy<Value = Position> {
+        (-1000isize..1000, -1000isize..1000).prop_map(|(top, left)| Position { top, left })
+    }
+
+    fn movement() -> impl Strategy<Value = Movement> {
+        (0..4, 0u32..10).prop_map(|(direction, steps)| match direction {
+            0 => Movement::Up(steps),
+            1 => Movement::Down(steps),
+            2 => Movement::Left(steps),
+            _ => Movement::Right(steps),
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn follow_matches_reference(knot in position(), leader in position()) {
+            let mut followed = knot.clone();
+            followed.follow(&leader);
+            prop_assert_eq!(reference_follow(&knot, &leader), followed);
+        }
+
+        #[test]
+        fn follow_keeps_a_chain_together(
+            knots in 1usize..12,
+            movements in prop::collection::vec(movement(), 0..50),
+        ) {
+            let mut rope = Rope::with_knots(knots);
+            let mut reference = vec![Position::default(); knots];
+            for movement in movements {
+                let (steps, top, left) = movement.steps();
+                for _ in 0..steps {
+                    reference[0].top += top;
+                    reference[0].left += left;
+                    for index in 1..knots {
+                        reference[index] = reference_follow(&reference[index], &reference[index - 1]);
+                    }
+                }
+                rope = move_head(rope, movement);
+                let mut leader = &rope.head;
+                for knot in rope.knots.iter() {
+                    prop_assert!(knot.touches(leader));
+                    leader = knot;
+                }
+            }
+            prop_assert_eq!(&reference[0], &rope.head);
+            prop_assert_eq!(&reference[1..], &rope.knots[..]);
+            prop_assert_eq!(reference.last(), rope.tail.last());
+        }
+    }
+
     #[test]
     fn parsing() {
         assert_eq!(Ok(Movement::Down(2)), "D 2".parse());
@@ -119,9 +250,39 @@ mod tests {
         assert_eq!(Ok(Movement::Left(2)), "L 2".parse());
         assert_eq!(Ok(Movement::Right(2)), "R 2".parse());
         assert_eq!(
-            Err(Ooops("invalid movement 'banana'".to_string())),
+            Err(Ooops::parse("movement", "banana")),
             "banana".parse::<Movement>()
         );
+        assert_eq!(
+            Err(Ooops::parse("movement", "R x").at_line(2)),
+            Day9::parse_with("U 1\nR x\nL 3", &mut Diagnostics::new(Mode::Strict))
+        );
+        let lenient = Day9::solve_with("R 1\nbanana\nU 1", Part::A, Mode::Lenient).unwrap();
+        assert_eq!(
+            vec![Ooops::parse("movement", "banana").at_line(2).in_day(9)],
+            lenient.skipped
+        );
+    }
+
+    #[test]
+    fn ropes_need_a_knot() {
+        let error = Ooops::new(ErrorKind::OutOfRange("knots"), "0").in_day(9);
+        assert_eq!(Err(error.clone()), crate::tail_visits_with_knots("R 4", 0));
+        assert_eq!(Err(error.clone()), crate::rope_frame("R 4", 0, 1));
+        let path = std::env::temp_dir().join("aoc-no-knots.pgm");
+        assert_eq!(Err(error), crate::export_tail_visits("R 4", 0, &path));
+        assert!(!path.exists());
+        // a single knot is both the head and the tail
+        assert_eq!(Ok(5), crate::tail_visits_with_knots("R 4", 1));
+    }
+
+    #[test]
+    fn moving_knots() {
+        let small = Day9::parse("R 4\nU 4\nL 3\nD 1\nR 4\nD 1\nL 5\nR 2").unwrap();
+        assert_eq!(13, tail_visits(&small, 2));
+        assert_eq!(1, tail_visits(&small, 10));
+        let large = Day9::parse("R 5\nU 8\nL 8\nD 3\nR 17\nD 10\nL 25\nU 20").unwrap();
+        assert_eq!(36, tail_visits(&large, 10));
     }
 
     #[test]
@@ -131,6 +292,7 @@ mod tests {
         assert_eq!(
             Rope {
                 head: Position { top: 1, left: 0 },
+                knots: vec![Position { top: 0, left: 0 }],
                 tail: vec![Position { top: 0, left: 0 }]
             },
             rope
@@ -139,6 +301,7 @@ mod tests {
         assert_eq!(
             Rope {
                 head: Position { top: 2, left: 0 },
+                knots: vec![Position { top: 1, left: 0 }],
                 tail: vec![Position { top: 0, left: 0 }, Position { top: 1, left: 0 }]
             },
             rope
@@ -147,6 +310,7 @@ mod tests {
         assert_eq!(
             Rope {
                 head: Position { top: 3, left: 0 },
+                knots: vec![Position { top: 2, left: 0 }],
                 tail: vec![
                     Position { top: 0, left: 0 },
                     Position { top: 1, left: 0 },
@@ -159,6 +323,7 @@ mod tests {
         assert_eq!(
             Rope {
                 head: Position { top: 4, left: 0 },
+                knots: vec![Position { top: 3, left: 0 }],
                 tail: vec![
                     Position { top: 0, left: 0 },
                     Position { top: 1, left: 0 },
@@ -172,6 +337,7 @@ mod tests {
         assert_eq!(
             Rope {
                 head: Position { top: 6, left: 0 },
+                knots: vec![Position { top: 5, left: 0 }],
                 tail: vec![
                     Position { top: 0, left: 0 },
                     Position { top: 1, left: 0 },
@@ -187,6 +353,7 @@ mod tests {
         assert_eq!(
             Rope {
                 head: Position { top: 5, left: 0 },
+                knots: vec![Position { top: 5, left: 0 }],
                 tail: vec![
                     Position { top: 0, left: 0 },
                     Position { top: 1, left: 0 },
@@ -202,6 +369,7 @@ mod tests {
         assert_eq!(
             Rope {
                 head: Position { top: 4, left: 0 },
+                knots: vec![Position { top: 5, left: 0 }],
                 tail: vec![
                     Position { top: 0, left: 0 },
                     Position { top: 1, left: 0 },
@@ -217,6 +385,7 @@ mod tests {
         assert_eq!(
             Rope {
                 head: Position { top: 3, left: 0 },
+                knots: vec![Position { top: 4, left: 0 }],
                 tail: vec![
                     Position { top: 0, left: 0 },
                     Position { top: 1, left: 0 },
@@ -233,6 +402,7 @@ mod tests {
         assert_eq!(
             Rope {
                 head: Position { top: 2, left: 0 },
+                knots: vec![Position { top: 3, left: 0 }],
                 tail: vec![
                     Position { top: 0, left: 0 },
                     Position { top: 1, left: 0 },
@@ -250,6 +420,7 @@ mod tests {
         assert_eq!(
             Rope {
                 head: Position { top: -1, left: 0 },
+                knots: vec![Position { top: 0, left: 0 }],
                 tail: vec![
                     Position { top: 0, left: 0 },
                     Position { top: 1, left: 0 },
@@ -270,6 +441,7 @@ mod tests {
         assert_eq!(
             Rope {
                 head: Position { top: -1, left: -1 },
+                knots: vec![Position { top: 0, left: 0 }],
                 tail: vec![
                     Position { top: 0, left: 0 },
                     Position { top: 1, left: 0 },
@@ -290,6 +462,7 @@ mod tests {
         assert_eq!(
             Rope {
                 head: Position { top: -1, left: -2 },
+                knots: vec![Position { top: -1, left: -1 }],
                 tail: vec![
                     Position { top: 0, left: 0 },
                     Position { top: 1, left: 0 },
@@ -311,6 +484,7 @@ mod tests {
         assert_eq!(
             Rope {
                 head: Position { top: -1, left: -3 },
+                knots: vec![Position { top: -1, left: -2 }],
                 tail: vec![
                     Position { top: 0, left: 0 },
                     Position { top: 1, left: 0 },
@@ -333,6 +507,7 @@ mod tests {
         assert_eq!(
             Rope {
                 head: Position { top: -1, left: -6 },
+                knots: vec![Position { top: -1, left: -5 }],
                 tail: vec![
                     Position { top: 0, left: 0 },
                     Position { top: 1, left: 0 },
@@ -358,6 +533,7 @@ mod tests {
         assert_eq!(
             Rope {
                 head: Position { top: -1, left: -5 },
+                knots: vec![Position { top: -1, left: -5 }],
                 tail: vec![
                     Position { top: 0, left: 0 },
                     Position { top: 1, left: 0 },
@@ -383,6 +559,7 @@ mod tests {
         assert_eq!(
             Rope {
                 head: Position { top: -1, left: -4 },
+                knots: vec![Position { top: -1, left: -5 }],
                 tail: vec![
                     Position { top: 0, left: 0 },
                     Position { top: 1, left: 0 },
@@ -408,6 +585,7 @@ mod tests {
         assert_eq!(
             Rope {
                 head: Position { top: -1, left: 0 },
+                knots: vec![Position { top: -1, left: -1 }],
                 tail: vec![
                     Position { top: 0, left: 0 },
                     Position { top: 1, left: 0 },
@@ -437,6 +615,7 @@ mod tests {
         assert_eq!(
             Rope {
                 head: Position { top: 0, left: 0 },
+                knots: vec![Position { top: -1, left: -1 }],
                 tail: vec![
                     Position { top: 0, left: 0 },
                     Position { top: 1, left: 0 },
@@ -466,6 +645,7 @@ mod tests {
         assert_eq!(
             Rope {
                 head: Position { top: 1, left: 0 },
+                knots: vec![Position { top: 0, left: 0 }],
                 tail: vec![
                     Position { top: 0, left: 0 },
                     Position { top: 1, left: 0 },
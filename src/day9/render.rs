@@ -0,0 +1,171 @@
+use std::{collections::HashSet, io::Write};
+
+use super::{move_head, Movement, Position, Rope};
+
+/// Every state of a rope, one frame per step of the head (frame 0 being the initial state).
+/// Each frame lists the head followed by the other knots.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct RopeHistory {
+    frames: Vec<Vec<Position>>,
+}
+
+fn snapshot(rope: &Rope) -> Vec<Position> {
+    let mut knots = vec![rope.head.clone()];
+    knots.extend(rope.knots.iter().cloned());
+    knots
+}
+
+impl RopeHistory {
+    pub(crate) fn record(movements: &[Movement], knots: usize) -> Self {
+        let mut rope = Rope::with_knots(knots);
+        let mut frames = vec![snapshot(&rope)];
+        for movement in movements {
+            let (steps, _, _) = movement.steps();
+            for _ in 0..steps {
+                rope = move_head(rope, movement.unit());
+                frames.push(snapshot(&rope));
+            }
+        }
+        Self { frames }
+    }
+
+    pub(crate) fn steps(&self) -> usize {
+        self.frames.len() - 1
+    }
+
+    /// Top-left and bottom-right corners of the box holding every position of every frame
+    fn bounds(&self) -> (Position, Position) {
+        let positions = self.frames.iter().flatten();
+        let top = positions.clone().map(|p| p.top);
+        let left = positions.map(|p| p.left);
+        (
+            Position {
+                top: top.clone().max().unwrap_or(0),
+                left: left.clone().min().unwrap_or(0),
+            },
+            Position {
+                top: top.min().unwrap_or(0),
+                left: left.max().unwrap_or(0),
+            },
+        )
+    }
+
+    /// Cells visited by the tail up to the given step
+    fn visited(&self, step: usize) -> HashSet<&Position> {
+        self.frames[..=step]
+            .iter()
+            .filter_map(|frame| frame.last())
+            .collect()
+    }
+
+    fn label(&self, knot: usize) -> char {
+        match (knot, self.frames[0].len()) {
+            (0, _) => 'H',
+            (1, 2) => 'T',
+            (knot, _) => char::from_digit(knot as u32, 36).unwrap_or('?'),
+        }
+    }
+
+    /// The rope after `step` steps, drawn as in the puzzle: `H` for the head, `1..9` (or `T`) for the
+    /// other knots, `s` for the start and `#` for the cells visited by the tail so far
+    pub(crate) fn frame(&self, step: usize) -> Option<String> {
+        let frame = self.frames.get(step)?;
+        let visited = self.visited(step);
+        let (top_left, bottom_right) = self.bounds();
+        let start = Position::default();
+        let mut lines = vec![];
+        for top in (bottom_right.top..=top_left.top).rev() {
+            let line = (top_left.left..=bottom_right.left)
+                .map(|left| {
+                    let cell = Position { top, left };
+                    match frame.iter().position(|knot| *knot == cell) {
+                        Some(knot) => self.label(knot),
+                        None if cell == start => 's',
+                        None if visited.contains(&cell) => '#',
+                        None => '.',
+                    }
+                })
+                .collect::<String>();
+            lines.push(line);
+        }
+        Some(lines.join("\n"))
+    }
+
+    /// Binary PGM of every cell visited by the tail: white when visited, gray for the start
+    pub(crate) fn write_pgm(&self, out: &mut impl Write) -> std::io::Result<()> {
+        let visited = self.visited(self.steps());
+        let (top_left, bottom_right) = self.bounds();
+        let width = bottom_right.left - top_left.left + 1;
+        let height = top_left.top - bottom_right.top + 1;
+        write!(out, "P5\n{} {}\n255\n", width, height)?;
+        let start = Position::default();
+        let mut pixels = Vec::with_capacity((width * height) as usize);
+        for top in (bottom_right.top..=top_left.top).rev() {
+            for left in top_left.left..=bottom_right.left {
+                let cell = Position { top, left };
+                pixels.push(match cell {
+                    _ if cell == start => 128,
+                    _ if visited.contains(&cell) => 255,
+                    _ => 0,
+                });
+            }
+        }
+        out.write_all(&pixels)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{day9::Day9, solution::Solution};
+
+    use super::*;
+
+    fn history(knots: usize) -> RopeHistory {
+        let movements = Day9::parse("R 4\nU 4\nL 3\nD 1\nR 4\nD 1\nL 5\nR 2").unwrap();
+        RopeHistory::record(&movements, knots)
+    }
+
+    #[test]
+    fn frames() {
+        let history = history(2);
+        assert_eq!(24, history.steps());
+        assert_eq!(
+            Some(["......", "......", "......", "......", "H....."].join("\n")),
+            history.frame(0)
+        );
+        assert_eq!(
+            Some(["......", "......", "......", "......", "s#TH.."].join("\n")),
+            history.frame(3)
+        );
+        assert_eq!(
+            Some(["..##..", "...##.", ".TH##.", "....#.", "s###.."].join("\n")),
+            history.frame(24)
+        );
+        assert_eq!(None, history.frame(25));
+    }
+
+    #[test]
+    fn frames_with_knots() {
+        let history = history(10);
+        assert_eq!(
+            Some(["......", "......", "......", "......", "4321H."].join("\n")),
+            history.frame(4)
+        );
+        assert_eq!(
+            Some([".H1...", "...2..", "..43..", ".5....", "6....."].join("\n")),
+            history.frame(11)
+        );
+    }
+
+    #[test]
+    fn pgm() {
+        let mut image = vec![];
+        history(2).write_pgm(&mut image).unwrap();
+        let header = b"P5\n6 5\n255\n";
+        assert_eq!(header, &image[..header.len()]);
+        let pixels = &image[header.len()..];
+        assert_eq!(30, pixels.len());
+        assert_eq!(12, pixels.iter().filter(|p| **p == 255).count());
+        assert_eq!(128, pixels[24]);
+    }
+}
@@ -1,13 +1,284 @@
-use std::fmt::Display;
+use std::{fmt::Display, sync::Arc};
+
+/// What went wrong. The `&'static str` names the thing involved, e.g. `"action"`
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ErrorKind {
+    /// The text is not a valid value
+    Parse(&'static str),
+    /// A required section of the input is missing
+    MissingSection(&'static str),
+    /// An index points outside of the available values
+    OutOfRange(&'static str),
+    /// The input contradicts itself or the current state
+    InconsistentState(Inconsistency),
+    /// There is nothing to be found
+    NotFound(Missing),
+    /// Reading or writing outside of the lib failed
+    Io(IoOperation),
+}
+
+/// How the input contradicts itself or the current state
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Inconsistency {
+    /// A grid gets more or fewer cells than its size tells
+    CellCount {
+        /// Cells given
+        cells: usize,
+        /// Grid width
+        width: usize,
+        /// Grid height
+        height: usize,
+    },
+    /// A grid row is not as wide as the first one
+    RowWidth {
+        /// Width of the row
+        width: usize,
+        /// Width of the first row
+        expected: usize,
+    },
+    /// A `cd` into a directory never listed
+    UnknownDirectory,
+    /// A file listed again with another size
+    FileSize {
+        /// Size in the previous listing
+        before: usize,
+        /// Size in the new listing
+        after: usize,
+    },
+    /// A new listing has an entry the previous listing of the directory misses
+    MissingFromPreviousListing,
+    /// A previous listing of the directory has an entry the new listing misses
+    MissingFromNewListing,
+    /// The same name is listed both as a file and as a directory
+    FileAndDirectory,
+    /// A directory is never listed, so its size is unknown
+    NeverListed,
+    /// A layout has another number of stacks than the warehouse
+    StackCount {
+        /// Stacks in the layout
+        stacks: usize,
+        /// Stacks in the warehouse
+        expected: usize,
+    },
+    /// A layout has other crates than the warehouse
+    OtherCrates,
+}
+
+impl Display for Inconsistency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Inconsistency::CellCount { .. } => "cell count differs from the grid size",
+            Inconsistency::RowWidth { .. } => "row width differs from the first row",
+            Inconsistency::UnknownDirectory => "cd into unknown directory",
+            Inconsistency::FileSize { .. } => "file size differs from the previous listing",
+            Inconsistency::MissingFromPreviousListing => "entry missing from the previous listing",
+            Inconsistency::MissingFromNewListing => "entry missing from a new listing",
+            Inconsistency::FileAndDirectory => "file and directory with the same name",
+            Inconsistency::NeverListed => "directory never listed, its size is unknown",
+            Inconsistency::StackCount { .. } => "stack count differs from the warehouse",
+            Inconsistency::OtherCrates => "crates differ from the warehouse",
+        })
+    }
+}
+
+/// What could not be found
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Missing {
+    /// No solution for the day
+    Solution,
+    /// No `dayN.txt` in the input directory
+    InputFile,
+    /// No input embedded for the day
+    EmbeddedInput,
+    /// No marker of the given size in the datastream
+    Marker,
+    /// No directory frees enough space
+    DirectoryToDelete,
+    /// No moves reach the target
+    Plan,
+    /// The search gave up before finding moves reaching the target
+    PlanWithinLimit,
+}
+
+impl Display for Missing {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Missing::Solution => "solution for day",
+            Missing::InputFile => "input file",
+            Missing::EmbeddedInput => "embedded input for day",
+            Missing::Marker => "marker of size",
+            Missing::DirectoryToDelete => "directory large enough to free",
+            Missing::Plan => "plan reaching the target",
+            Missing::PlanWithinLimit => "plan within the search limit",
+        })
+    }
+}
+
+/// Whether the lib was reading or writing
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum IoOperation {
+    /// Reading a file or a stream
+    Read,
+    /// Writing a file
+    Write,
+}
+
+impl Display for IoOperation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IoOperation::Read => f.write_str("read"),
+            IoOperation::Write => f.write_str("write"),
+        }
+    }
+}
 
 /// Lib errors
-#[derive(Debug, PartialEq, Eq)]
-pub struct Ooops(pub String);
+#[derive(Debug, Clone)]
+pub struct Ooops {
+    /// What went wrong
+    pub kind: ErrorKind,
+    /// The puzzle day, when known
+    pub day: Option<usize>,
+    /// The failing line number (1-based), when known
+    pub line: Option<usize>,
+    /// The offending text
+    pub text: String,
+    source: Option<Arc<dyn std::error::Error + Send + Sync>>,
+}
+
+/// Two errors are equal when they say the same, whatever their source
+impl PartialEq for Ooops {
+    fn eq(&self, other: &Self) -> bool {
+        (self.kind, self.day, self.line, &self.text)
+            == (other.kind, other.day, other.line, &other.text)
+    }
+}
 
-impl std::error::Error for Ooops {}
+impl Eq for Ooops {}
+
+impl Ooops {
+    pub(crate) fn new(kind: ErrorKind, text: impl Into<String>) -> Self {
+        Self {
+            kind,
+            day: None,
+            line: None,
+            text: text.into(),
+            source: None,
+        }
+    }
+
+    pub(crate) fn parse(what: &'static str, text: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Parse(what), text)
+    }
+
+    /// A failed read or write of `what`, usually a path, keeping the I/O error as the source
+    pub(crate) fn io(operation: IoOperation, what: impl Display, e: std::io::Error) -> Self {
+        Self::new(ErrorKind::Io(operation), what.to_string()).caused_by(e)
+    }
+
+    /// Sets the day, unless it is already known
+    pub(crate) fn in_day(mut self, day: usize) -> Self {
+        self.day = self.day.or(Some(day));
+        self
+    }
+
+    /// Sets the line number, unless it is already known
+    pub(crate) fn at_line(mut self, line: usize) -> Self {
+        self.line = self.line.or(Some(line));
+        self
+    }
+
+    pub(crate) fn caused_by(
+        mut self,
+        source: impl std::error::Error + Send + Sync + 'static,
+    ) -> Self {
+        self.source = Some(Arc::new(source));
+        self
+    }
+}
+
+impl std::error::Error for Ooops {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.source
+            .as_deref()
+            .map(|e| e as &(dyn std::error::Error + 'static))
+    }
+}
 
 impl Display for Ooops {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.0)
+        match (self.day, self.line) {
+            (Some(day), Some(line)) => write!(f, "day {}, line {}: ", day, line)?,
+            (Some(day), None) => write!(f, "day {}: ", day)?,
+            (None, Some(line)) => write!(f, "line {}: ", line)?,
+            (None, None) => {}
+        }
+        match self.kind {
+            ErrorKind::Parse(what) => write!(f, "invalid {} '{}'", what, self.text),
+            ErrorKind::MissingSection(what) => write!(f, "missing {}", what),
+            ErrorKind::OutOfRange(what) => write!(f, "{} '{}' is out of range", what, self.text),
+            ErrorKind::InconsistentState(what) => write!(f, "{} '{}'", what, self.text),
+            ErrorKind::NotFound(what) => write!(f, "no {} '{}'", what, self.text),
+            ErrorKind::Io(what) => match &self.source {
+                Some(source) => write!(f, "cannot {} {}: {}", what, self.text, source),
+                None => write!(f, "cannot {} {}", what, self.text),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use super::*;
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            "invalid action 'banana'",
+            Ooops::parse("action", "banana").to_string()
+        );
+        assert_eq!(
+            "day 5, line 12: invalid action 'banana'",
+            Ooops::parse("action", "banana")
+                .at_line(12)
+                .in_day(5)
+                .to_string()
+        );
+        assert_eq!(
+            "day 7: cd into unknown directory 'a'",
+            Ooops::new(
+                ErrorKind::InconsistentState(Inconsistency::UnknownDirectory),
+                "a"
+            )
+            .in_day(7)
+            .in_day(8)
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn source_chaining() {
+        let e = "x".parse::<usize>().unwrap_err();
+        let ooops = Ooops::parse("range", "x-4").caused_by(e.clone());
+        assert_eq!(
+            Some(e.to_string()),
+            ooops.source().map(|source| source.to_string())
+        );
+        assert!(Ooops::parse("range", "x-4").source().is_none());
+        assert_eq!(ooops.clone(), Ooops::parse("range", "x-4"));
+
+        let e = std::io::Error::from(std::io::ErrorKind::PermissionDenied);
+        let ooops = Ooops::io(IoOperation::Read, "day5.txt", e);
+        assert_eq!("cannot read day5.txt: permission denied", ooops.to_string());
+        assert_eq!(
+            Some(std::io::ErrorKind::PermissionDenied),
+            ooops
+                .clone()
+                .source()
+                .and_then(|source| source.downcast_ref::<std::io::Error>())
+                .map(|e| e.kind())
+        );
     }
 }
@@ -0,0 +1,378 @@
+use std::{
+    fmt::Display,
+    ops::{Index, IndexMut},
+};
+
+use crate::error::{ErrorKind, Inconsistency, Ooops};
+
+/// Position of a cell, counted from the top-left corner of the grid
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Clone, Copy, Default)]
+pub struct Coord {
+    /// Row, 0 being the top one
+    pub top: usize,
+    /// Column, 0 being the leftmost one
+    pub left: usize,
+}
+
+impl Coord {
+    /// The cell at the given row and column
+    pub fn new(top: usize, left: usize) -> Self {
+        Self { top, left }
+    }
+
+    /// The next cell towards `direction`, unless it falls off the top or the left of the grid
+    pub fn step(self, direction: Direction) -> Option<Coord> {
+        let (top, left) = direction.offset();
+        Some(Coord {
+            top: self.top.checked_add_signed(top)?,
+            left: self.left.checked_add_signed(left)?,
+        })
+    }
+}
+
+/// Where to go from a cell. The diagonals combine two of the cardinal directions
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Direction {
+    /// Towards the top row
+    Up,
+    /// Towards the bottom row
+    Down,
+    /// Towards the leftmost column
+    Left,
+    /// Towards the rightmost column
+    Right,
+    /// Up and left
+    UpLeft,
+    /// Up and right
+    UpRight,
+    /// Down and left
+    DownLeft,
+    /// Down and right
+    DownRight,
+}
+
+impl Direction {
+    /// The directions of the 4-connected neighbours
+    pub const CARDINAL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Down,
+        Direction::Left,
+        Direction::Right,
+    ];
+
+    /// The directions of the 8-connected neighbours
+    pub const ALL: [Direction; 8] = [
+        Direction::Up,
+        Direction::Down,
+        Direction::Left,
+        Direction::Right,
+        Direction::UpLeft,
+        Direction::UpRight,
+        Direction::DownLeft,
+        Direction::DownRight,
+    ];
+
+    /// Rows and columns moved by a single step
+    pub fn offset(self) -> (isize, isize) {
+        match self {
+            Direction::Up => (-1, 0),
+            Direction::Down => (1, 0),
+            Direction::Left => (0, -1),
+            Direction::Right => (0, 1),
+            Direction::UpLeft => (-1, -1),
+            Direction::UpRight => (-1, 1),
+            Direction::DownLeft => (1, -1),
+            Direction::DownRight => (1, 1),
+        }
+    }
+
+    /// The direction pointing the other way
+    pub fn opposite(self) -> Direction {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+            Direction::UpLeft => Direction::DownRight,
+            Direction::UpRight => Direction::DownLeft,
+            Direction::DownLeft => Direction::UpRight,
+            Direction::DownRight => Direction::UpLeft,
+        }
+    }
+}
+
+/// Rectangle of cells, stored row by row
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// A grid of the given size from its cells, row by row
+    pub fn new(width: usize, height: usize, cells: Vec<T>) -> Result<Self, Ooops> {
+        if cells.len() != width * height {
+            return Err(Ooops::new(
+                ErrorKind::InconsistentState(Inconsistency::CellCount {
+                    cells: cells.len(),
+                    width,
+                    height,
+                }),
+                format!("{} for {}x{}", cells.len(), width, height),
+            ));
+        }
+        Ok(Self {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    /// Parses every non-empty line as a row, turning each character into a cell
+    pub fn parse(values: &str, cell: impl Fn(char) -> T) -> Result<Self, Ooops> {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = vec![];
+        for (index, line) in values.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let row = line.chars().map(&cell).collect::<Vec<T>>();
+            if *width.get_or_insert(row.len()) != row.len() {
+                return Err(Ooops::new(
+                    ErrorKind::InconsistentState(Inconsistency::RowWidth {
+                        width: row.len(),
+                        expected: width.unwrap_or(0),
+                    }),
+                    line,
+                )
+                .at_line(index + 1));
+            }
+            cells.extend(row);
+            height += 1;
+        }
+        Self::new(width.unwrap_or(0), height, cells)
+    }
+
+    /// Number of columns
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Number of rows
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Whether `coord` is inside of the grid
+    pub fn contains(&self, coord: Coord) -> bool {
+        coord.top < self.height && coord.left < self.width
+    }
+
+    /// The cell at `coord`, if inside of the grid
+    pub fn get(&self, coord: Coord) -> Option<&T> {
+        self.contains(coord)
+            .then(|| &self.cells[coord.top * self.width + coord.left])
+    }
+
+    /// The cell at `coord`, if inside of the grid
+    pub fn get_mut(&mut self, coord: Coord) -> Option<&mut T> {
+        match self.contains(coord) {
+            true => Some(&mut self.cells[coord.top * self.width + coord.left]),
+            false => None,
+        }
+    }
+
+    /// Every coordinate, row by row
+    pub fn coords(&self) -> impl Iterator<Item = Coord> {
+        let width = self.width;
+        (0..self.cells.len()).map(move |index| Coord::new(index / width, index % width))
+    }
+
+    /// Every cell with its coordinate, row by row
+    pub fn iter(&self) -> impl Iterator<Item = (Coord, &T)> {
+        self.coords().zip(self.cells.iter())
+    }
+
+    /// The next cell from `coord` towards `direction`, if inside of the grid
+    pub fn neighbour(&self, coord: Coord, direction: Direction) -> Option<Coord> {
+        coord.step(direction).filter(|next| self.contains(*next))
+    }
+
+    /// The 4-connected neighbours of `coord` inside of the grid
+    pub fn neighbours(&self, coord: Coord) -> impl Iterator<Item = Coord> + '_ {
+        Direction::CARDINAL
+            .into_iter()
+            .filter_map(move |direction| self.neighbour(coord, direction))
+    }
+
+    /// The 8-connected neighbours of `coord` inside of the grid
+    pub fn neighbours8(&self, coord: Coord) -> impl Iterator<Item = Coord> + '_ {
+        Direction::ALL
+            .into_iter()
+            .filter_map(move |direction| self.neighbour(coord, direction))
+    }
+
+    /// The cells met walking from `coord` (excluded) towards `direction`, up to the edge
+    pub fn ray(&self, coord: Coord, direction: Direction) -> impl Iterator<Item = (Coord, &T)> {
+        std::iter::successors(self.neighbour(coord, direction), move |current| {
+            self.neighbour(*current, direction)
+        })
+        .map(|coord| (coord, &self[coord]))
+    }
+
+    /// The cells on the side of the grid facing `direction`, i.e. the ones a step away from leaving it
+    pub fn edge(&self, direction: Direction) -> Vec<Coord> {
+        self.coords()
+            .filter(|coord| self.neighbour(*coord, direction).is_none())
+            .collect()
+    }
+
+    /// The cells of a row, if inside of the grid
+    pub fn row(&self, top: usize) -> Option<&[T]> {
+        (top < self.height).then(|| &self.cells[top * self.width..(top + 1) * self.width])
+    }
+
+    /// The cells of a column, top to bottom, if inside of the grid
+    pub fn column(&self, left: usize) -> Option<impl Iterator<Item = &T>> {
+        (left < self.width).then(|| self.cells.iter().skip(left).step_by(self.width))
+    }
+
+    /// A grid of the same size, with `f` applied to every cell
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// A grid of the given size with every cell set to `value`
+    pub fn filled(width: usize, height: usize, value: T) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+}
+
+impl<T> Index<Coord> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, coord: Coord) -> &Self::Output {
+        self.get(coord).expect("coordinate inside of the grid")
+    }
+}
+
+impl<T> IndexMut<Coord> for Grid<T> {
+    fn index_mut(&mut self, coord: Coord) -> &mut Self::Output {
+        self.get_mut(coord).expect("coordinate inside of the grid")
+    }
+}
+
+/// One line per row, each cell printed as is
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for top in 0..self.height {
+            if top > 0 {
+                writeln!(f)?;
+            }
+            for cell in self.row(top).unwrap_or_default() {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Grid<char> {
+        Grid::parse("abc\ndef\n", |c| c).unwrap()
+    }
+
+    #[test]
+    fn parse_and_display() {
+        let grid = example();
+        assert_eq!((3, 2), (grid.width(), grid.height()));
+        assert_eq!('f', grid[Coord::new(1, 2)]);
+        assert_eq!(None, grid.get(Coord::new(2, 0)));
+        assert_eq!("abc\ndef", grid.to_string());
+        assert_eq!(
+            Err(Ooops::new(
+                ErrorKind::InconsistentState(Inconsistency::RowWidth {
+                    width: 2,
+                    expected: 3
+                }),
+                "de"
+            )
+            .at_line(2)),
+            Grid::parse("abc\nde", |c| c)
+        );
+        assert_eq!(
+            Err(Ooops::new(
+                ErrorKind::InconsistentState(Inconsistency::CellCount {
+                    cells: 5,
+                    width: 3,
+                    height: 2
+                }),
+                "5 for 3x2"
+            )),
+            Grid::new(3, 2, vec![0; 5])
+        );
+    }
+
+    #[test]
+    fn neighbours() {
+        let grid = example();
+        assert_eq!(
+            vec![Coord::new(1, 0), Coord::new(0, 1)],
+            grid.neighbours(Coord::new(0, 0)).collect::<Vec<Coord>>()
+        );
+        assert_eq!(
+            vec![
+                Coord::new(0, 1),
+                Coord::new(1, 0),
+                Coord::new(1, 2),
+                Coord::new(0, 0),
+                Coord::new(0, 2)
+            ],
+            grid.neighbours8(Coord::new(1, 1)).collect::<Vec<Coord>>()
+        );
+    }
+
+    #[test]
+    fn rays_and_views() {
+        let grid = example();
+        assert_eq!(
+            vec!['b', 'c'],
+            grid.ray(Coord::new(0, 0), Direction::Right)
+                .map(|(_, c)| *c)
+                .collect::<Vec<char>>()
+        );
+        assert_eq!(None, grid.ray(Coord::new(0, 1), Direction::Up).next());
+        assert_eq!(
+            vec![(Coord::new(0, 1), &'b')],
+            grid.ray(Coord::new(1, 2), Direction::UpLeft)
+                .collect::<Vec<(Coord, &char)>>()
+        );
+        assert_eq!(
+            vec![Coord::new(1, 0), Coord::new(1, 1), Coord::new(1, 2)],
+            grid.edge(Direction::Down)
+        );
+        assert_eq!(Some(&['d', 'e', 'f'][..]), grid.row(1));
+        assert_eq!(
+            Some(vec![&'c', &'f']),
+            grid.column(2).map(|c| c.collect::<Vec<&char>>())
+        );
+        assert!(grid.column(3).is_none());
+        assert_eq!("bcd\nefg", grid.map(|c| (*c as u8 + 1) as char).to_string());
+    }
+}
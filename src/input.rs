@@ -1,3 +1,7 @@
+use std::{fs, path::PathBuf};
+
+use crate::error::{ErrorKind, IoOperation, Missing, Ooops};
+
 /// Input data for day 1
 pub const DAY1: &str = include_str!("day1.txt");
 
@@ -24,3 +28,109 @@ pub const DAY8: &str = include_str!("day8.txt");
 
 /// Input data for day 9
 pub const DAY9: &str = include_str!("day9.txt");
+
+/// Embedded input data for the given day
+pub fn day(day: usize) -> Option<&'static str> {
+    match day {
+        1 => Some(DAY1),
+        2 => Some(DAY2),
+        3 => Some(DAY3),
+        4 => Some(DAY4),
+        5 => Some(DAY5),
+        6 => Some(DAY6),
+        7 => Some(DAY7),
+        8 => Some(DAY8),
+        9 => Some(DAY9),
+        _ => None,
+    }
+}
+
+/// Environment variable with the directory holding the `dayN.txt` input files
+pub const INPUT_DIR_ENV: &str = "AOC_INPUT_DIR";
+
+/// Resolves the input of a day from a directory of `dayN.txt` files,
+/// or from the embedded inputs when no directory is configured
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct InputProvider {
+    dir: Option<PathBuf>,
+}
+
+impl InputProvider {
+    /// Reads the inputs from `dir`
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: Some(dir.into()),
+        }
+    }
+
+    /// Reads the inputs from the directory in [`INPUT_DIR_ENV`], if set
+    pub fn from_env() -> Self {
+        Self {
+            dir: std::env::var_os(INPUT_DIR_ENV).map(PathBuf::from),
+        }
+    }
+
+    /// The file expected to hold the input of the given day
+    pub fn path(&self, day: usize) -> Option<PathBuf> {
+        self.dir
+            .as_ref()
+            .map(|dir| dir.join(format!("day{}.txt", day)))
+    }
+
+    /// Input data for the given day
+    pub fn get(&self, day: usize) -> Result<String, Ooops> {
+        match self.path(day) {
+            Some(path) if path.is_file() => fs::read_to_string(&path)
+                .map_err(|e| Ooops::io(IoOperation::Read, path.display(), e).in_day(day)),
+            Some(path) => Err(Ooops::new(
+                ErrorKind::NotFound(Missing::InputFile),
+                path.display().to_string(),
+            )
+            .in_day(day)),
+            None => self::day(day)
+                .map(|values| values.to_string())
+                .ok_or_else(|| {
+                    Ooops::new(ErrorKind::NotFound(Missing::EmbeddedInput), day.to_string())
+                }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn embedded_input() {
+        let provider: InputProvider = Default::default();
+        assert_eq!(Ok(DAY4.to_string()), provider.get(4));
+        assert_eq!(
+            Err(Ooops::new(
+                ErrorKind::NotFound(Missing::EmbeddedInput),
+                "25"
+            )),
+            provider.get(25)
+        );
+    }
+
+    #[test]
+    fn input_from_directory() {
+        let dir = std::env::temp_dir().join(format!("aoc-input-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("day6.txt"), "mjqjpqmgbljsphdztnvjfqwrcgsmlb").unwrap();
+        let provider = InputProvider::new(&dir);
+        assert_eq!(
+            Ok("mjqjpqmgbljsphdztnvjfqwrcgsmlb".to_string()),
+            provider.get(6)
+        );
+        assert_eq!(
+            Err(Ooops::new(
+                ErrorKind::NotFound(Missing::InputFile),
+                dir.join("day7.txt").display().to_string()
+            )
+            .in_day(7)),
+            provider.get(7)
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
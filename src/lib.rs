@@ -13,156 +13,160 @@ mod day8;
 mod day9;
 mod error;
 
+/// Grids of cells, for the puzzles drawn in two dimensions
+pub mod grid;
+
 /// Input files
 pub mod input;
 
-use std::collections::HashSet;
-
-use day1::group_max;
-use day2::{CheatRpsMatch, RpsMatch};
-use day3::{priority, Rucksack};
-use day4::AssignmentPair;
-use day5::{ActionsLines, Warehouse};
-use day6::Stream;
-use day7::{input_to_root, FsItem};
-use day8::{
-    viewing_distance, BottomTrees, Direction, LeftTrees, RightTrees, TopTrees, Tree, Trees,
+/// Common interface and registry of the solved days
+pub mod solution;
+
+pub use day1::Day1;
+pub use day2::Day2;
+pub use day3::Day3;
+pub use day4::Day4;
+pub use day5::{
+    AdjacentCrane, CountingCrane, Crane, CraneType, CrateAction, Day5, LimitedCrane, MoveError,
+    MoveProblem, Replay, Target, Warehouse, PLAN_SEARCH_LIMIT,
 };
-use day9::{move_head, Position, Rope};
-use error::Ooops;
+pub use day6::Day6;
+pub use day7::{Cleanup, CleanupStrategy, Day7, DuOrder, FsChange, FsChangeKind, FsDiff, FsEntry};
+pub use day8::Day8;
+pub use day9::Day9;
+pub use error::{ErrorKind, Inconsistency, IoOperation, Missing, Ooops};
+
+use std::{
+    fs::File,
+    io::{BufRead, BufWriter, Write},
+    path::Path,
+};
+
+use day9::RopeHistory;
+use grid::{Coord, Grid};
+use solution::{Diagnostics, Mode, Part, Solution};
+
+/// Tags an error of the given day's helpers with the day
+fn in_day<S: Solution>(e: Ooops) -> Ooops {
+    e.in_day(S::DAY)
+}
+
+/// Creates the file at `path` and writes it through a buffer, flushed before returning so that
+/// a failed write is reported
+fn write_file(
+    path: &Path,
+    write: impl FnOnce(&mut BufWriter<File>) -> std::io::Result<()>,
+) -> Result<(), Ooops> {
+    let io_error = |e| Ooops::io(IoOperation::Write, path.display(), e);
+    let mut out = BufWriter::new(File::create(path).map_err(io_error)?);
+    write(&mut out).and_then(|_| out.flush()).map_err(io_error)
+}
 
 /// Part A -> <https://adventofcode.com/2022/day/1>
 pub fn total_of_calories_with_the_elf_with_the_most_calories(values: &str) -> usize {
-    return group_max(values).fold(0, usize::max);
+    Day1::solve(values, Part::A).expect("day 1 skips invalid lines")
 }
 
 /// Part B -> <https://adventofcode.com/2022/day/1>
 pub fn total_of_calories_for_the_top_three_elfs(values: &str) -> usize {
-    let mut values = group_max(values).collect::<Vec<usize>>();
-    values.sort();
-    values.reverse();
-    let values = values.iter();
-    values.take(3).sum()
+    Day1::solve(values, Part::B).expect("day 1 skips invalid lines")
 }
 
 /// Part A -> <https://adventofcode.com/2022/day/2>
 pub fn total_score_according_to_your_strategy_guide(values: &str) -> usize {
-    values
-        .trim()
-        .split('\n')
-        .map(|v| v.trim())
-        .filter(|v| !v.is_empty())
-        .filter_map(|v| v.parse::<RpsMatch>().ok())
-        .map(|v| v.play())
-        .sum()
+    Day2::solve(values, Part::A).expect("day 2 skips invalid lines")
 }
 
 /// Part B -> <https://adventofcode.com/2022/day/2>
 pub fn total_score_according_to_the_elfs_strategy_guide(values: &str) -> usize {
-    values
-        .trim()
-        .split('\n')
-        .map(|v| v.trim())
-        .filter(|v| !v.is_empty())
-        .filter_map(|v| v.parse::<CheatRpsMatch>().ok())
-        .map(|v| v.play())
-        .sum()
+    Day2::solve(values, Part::B).expect("day 2 skips invalid lines")
 }
 
 /// Part A -> <https://adventofcode.com/2022/day/3>
 pub fn the_sum_of_the_priorities_for_shared_item_types(values: &str) -> usize {
-    values
-        .trim()
-        .lines()
-        .map(|v| v.trim())
-        .filter(|v| !v.is_empty())
-        .filter_map(|v| v.parse::<Rucksack>().ok())
-        .map(|v| {
-            v.shared
-                .iter()
-                .map(priority)
-                .filter_map(|v| v.ok())
-                .sum::<usize>()
-        })
-        .sum()
+    Day3::solve(values, Part::A).expect("day 3 skips invalid lines")
 }
 
 /// Part B -> <https://adventofcode.com/2022/day/3>
 pub fn the_sum_of_the_priorities_for_shared_item_types_in_three_elfs_group(values: &str) -> usize {
-    let mut iter = values
-        .trim()
-        .lines()
-        .map(|v| v.trim())
-        .filter(|v| !v.is_empty())
-        .map(|v| v.parse::<Rucksack>())
-        .filter_map(|v| v.ok());
-    let mut results = vec![];
-    while let (Some(one), Some(two), Some(three)) = (iter.next(), iter.next(), iter.next()) {
-        results.push(one.intersection(vec![&two, &three]));
-    }
-    results
-        .iter()
-        .map(|v| v.iter().filter_map(|v| priority(v).ok()).sum::<usize>())
-        .sum()
+    Day3::solve(values, Part::B).expect("day 3 skips invalid lines")
 }
 
 /// Part A -> <https://adventofcode.com/2022/day/4>
 pub fn how_many_pairs_does_one_fully_contain_the_other(values: &str) -> usize {
-    values
-        .trim()
-        .lines()
-        .map(|v| v.trim())
-        .filter(|v| !v.is_empty())
-        .map(|v| v.parse::<AssignmentPair>())
-        .filter_map(|v| v.ok())
-        .filter(|v| v.a.fully_contains(&v.b) || v.b.fully_contains(&v.a))
-        .count()
+    Day4::solve(values, Part::A).expect("day 4 skips invalid lines")
 }
 
 /// Part B -> <https://adventofcode.com/2022/day/4>
 pub fn how_many_pairs_do_ranges_overlap(values: &str) -> usize {
-    values
-        .trim()
-        .lines()
-        .map(|v| v.trim())
-        .filter(|v| !v.is_empty())
-        .map(|v| v.parse::<AssignmentPair>())
-        .filter_map(|v| v.ok())
-        .filter(|v| v.a.overlaps(&v.b) || v.b.overlaps(&v.a))
-        .count()
+    Day4::solve(values, Part::B).expect("day 4 skips invalid lines")
 }
 
 /// Part A -> <https://adventofcode.com/2022/day/5>
 pub fn crates_on_top_of_each_stack(values: &str) -> Result<String, Ooops> {
-    let mut warehouse: Warehouse = values.parse()?;
-    let actions: ActionsLines = values.parse()?;
-    for action in actions {
-        warehouse.shuffle(&action);
-    }
-    Ok(warehouse.top_crates())
+    Day5::solve(values, Part::A)
 }
 
 /// Part B -> <https://adventofcode.com/2022/day/5>
 pub fn crates_on_top_of_each_stack_with_super_crane(values: &str) -> Result<String, Ooops> {
-    let mut warehouse: Warehouse = values.parse()?;
-    let actions: ActionsLines = values.parse()?;
-    for action in actions {
-        warehouse.shuffle_with_crane(&action, day5::CraneType::Super);
-    }
-    Ok(warehouse.top_crates())
+    Day5::solve(values, Part::B)
+}
+
+/// Every day 5 move that cannot be made as written, checked before applying any. A line that is
+/// not a move fails, so that moves keep their number in the program
+pub fn validate_crane_actions(values: &str) -> Result<Vec<MoveError>, Ooops> {
+    let (warehouse, actions) =
+        Day5::parse_with(values, &mut Diagnostics::new(Mode::Strict)).map_err(in_day::<Day5>)?;
+    Ok(warehouse.validate(&actions))
+}
+
+/// Day 5 top crates once the given crane made every move, with the total cost of the run
+pub fn operate_crane(values: &str, crane: &impl Crane) -> Result<(String, usize), Ooops> {
+    let (mut warehouse, actions) = Day5::parse(values)?;
+    let cost = warehouse.operate(&actions, crane);
+    Ok((warehouse.top_crates(), cost))
+}
+
+/// The fewest day 5 moves, one `move N from A to B` per line, rearranging the drawing of `values`
+/// into `target`: either the top crates or a whole drawing. The search gives up after `limit`
+/// warehouses, [`PLAN_SEARCH_LIMIT`] being enough for plans a few moves deep on small drawings
+pub fn plan_crane_actions(
+    values: &str,
+    target: &str,
+    crane: CraneType,
+    limit: usize,
+) -> Result<String, Ooops> {
+    let warehouse: Warehouse = values.parse().map_err(in_day::<Day5>)?;
+    let target: Target = target.parse().map_err(in_day::<Day5>)?;
+    let actions = day5::plan(&warehouse, &target, crane, limit).map_err(in_day::<Day5>)?;
+    Ok(actions
+        .iter()
+        .map(|action| action.to_string())
+        .collect::<Vec<String>>()
+        .join("\n"))
+}
+
+/// Streams the day 5 moves of any reader, one line at a time, skipping the drawing when there is one
+pub fn read_crane_actions<R: BufRead>(
+    reader: R,
+) -> impl Iterator<Item = Result<CrateAction, Ooops>> {
+    day5::ActionsReader::new(reader).map(|action| action.map_err(in_day::<Day5>))
+}
+
+/// Records the warehouse after each day 5 move made by the given crane, to step through them
+pub fn replay_crane_actions(values: &str, crane: CraneType) -> Result<Replay, Ooops> {
+    let (warehouse, actions) = Day5::parse(values)?;
+    Ok(Replay::record(warehouse, actions, crane))
 }
 
 /// Part A -> <https://adventofcode.com/2022/day/6>
 pub fn start_of_packet_marker_position(values: &str) -> Option<usize> {
-    let mut stream: Stream = values.into();
-    stream.find_map(|w| w.marker_position())
+    Day6::solve(values, Part::A).ok()
 }
 
 /// Part B -> <https://adventofcode.com/2022/day/6>
 pub fn start_of_message_marker_position(values: &str) -> Option<usize> {
-    let mut stream: Stream = values.into();
-    stream.window_size = 14;
-    stream.find_map(|w| w.marker_position())
+    Day6::solve(values, Part::B).ok()
 }
 
 /// Part A -> <https://adventofcode.com/2022/day/7>
@@ -170,19 +174,8 @@ pub fn sum_of_the_total_sizes_of_directories_smaller_than(
     values: &str,
     size: usize,
 ) -> Result<usize, Ooops> {
-    let root = input_to_root(values)?;
-    let directories = root.ls_directories();
-    let directories = directories
-        .iter()
-        .filter(|d| {
-            let d = d.borrow();
-            d.size() <= size
-        })
-        .map(|d| {
-            let d = d.borrow();
-            d.size()
-        });
-    Ok(directories.sum())
+    let fs = Day7::parse(values)?;
+    Ok(day7::sum_of_directories_smaller_than(&fs, size))
 }
 
 /// Part B -> <https://adventofcode.com/2022/day/7>
@@ -191,79 +184,164 @@ pub fn size_of_the_dir_to_be_deleted(
     fs_size: usize,
     required_free_space: usize,
 ) -> Result<usize, Ooops> {
-    let root = input_to_root(values)?;
-    let root_size = root.size();
-    let free_space = fs_size - root_size;
-    let to_be_free = required_free_space - free_space;
+    let fs = Day7::parse(values)?;
+    day7::size_of_the_dir_to_be_deleted(&fs, fs_size, required_free_space).map_err(in_day::<Day7>)
+}
 
-    let directories = root.ls_directories();
-    let directories = directories
-        .iter()
-        .filter(|d| {
-            let d = d.borrow();
-            d.size() >= to_be_free
-        })
-        .map(|d| {
-            let d = d.borrow();
-            d.size()
-        });
-    Ok(directories.min().unwrap_or(0))
+/// What to delete so that `required_free_space` is available on a disk of `fs_size`, see [`CleanupStrategy`]
+pub fn plan_cleanup(
+    values: &str,
+    fs_size: usize,
+    required_free_space: usize,
+    strategy: CleanupStrategy,
+) -> Result<Cleanup, Ooops> {
+    let fs = Day7::parse(values)?;
+    Ok(day7::plan(&fs, fs_size, required_free_space, strategy))
+}
+
+/// Everything contradicting itself in a day 7 terminal transcript, such as a `cd` into a directory
+/// never listed. Fails only when a line cannot be parsed
+pub fn validate_transcript(values: &str) -> Result<Vec<Ooops>, Ooops> {
+    let (_, issues) = day7::validate(values).map_err(in_day::<Day7>)?;
+    Ok(issues.into_iter().map(in_day::<Day7>).collect())
+}
+
+/// The filesystem as drawn in <https://adventofcode.com/2022/day/7>
+pub fn filesystem_tree(values: &str) -> Result<String, Ooops> {
+    let fs = Day7::parse(values)?;
+    Ok(day7::tree(&fs))
+}
+
+/// `du`-style listing of every directory, one `size<TAB>path` line each, down to `max_depth`
+pub fn disk_usage(values: &str, order: DuOrder, max_depth: Option<usize>) -> Result<String, Ooops> {
+    let fs = Day7::parse(values)?;
+    Ok(day7::du(&fs, order, max_depth)
+        .into_iter()
+        .map(|(path, size)| format!("{}\t{}", size, path))
+        .collect::<Vec<String>>()
+        .join("\n"))
+}
+
+/// The file or directory at an absolute path such as `/a/e`
+pub fn find_path(values: &str, path: &str) -> Result<Option<FsEntry>, Ooops> {
+    let fs = Day7::parse(values)?;
+    Ok(fs.lookup(path).map(|id| fs.entry(id)))
+}
+
+/// Every file and directory with its absolute path, each directory followed by its content
+pub fn filesystem_entries(values: &str) -> Result<Vec<(String, FsEntry)>, Ooops> {
+    let fs = Day7::parse(values)?;
+    Ok(fs.walk().map(|(path, id)| (path, fs.entry(id))).collect())
+}
+
+/// Files whose name matches a `*`/`?` glob, e.g. `*.txt`
+pub fn glob_files(values: &str, pattern: &str) -> Result<Vec<(String, FsEntry)>, Ooops> {
+    let fs = Day7::parse(values)?;
+    Ok(day7::find_files(&fs, pattern)
+        .into_iter()
+        .map(|(path, id)| (path, fs.entry(id)))
+        .collect())
+}
+
+/// The `n` biggest files with their absolute paths
+pub fn largest_files(values: &str, n: usize) -> Result<Vec<(String, FsEntry)>, Ooops> {
+    let fs = Day7::parse(values)?;
+    Ok(day7::largest_files(&fs, n)
+        .into_iter()
+        .map(|(path, id)| (path, fs.entry(id)))
+        .collect())
+}
+
+/// Creates the day 7 filesystem under `dir`, files being sparse ones of the recorded sizes. Any
+/// invalid line, such as a name leaving its directory, fails the export
+pub fn export_filesystem(values: &str, dir: impl AsRef<Path>) -> Result<(), Ooops> {
+    let fs =
+        Day7::parse_with(values, &mut Diagnostics::new(Mode::Strict)).map_err(in_day::<Day7>)?;
+    day7::write_to(&fs, dir.as_ref()).map_err(in_day::<Day7>)
+}
+
+/// Lists a real directory as a day 7 terminal transcript, which the day 7 solutions can read back
+pub fn import_filesystem(dir: impl AsRef<Path>) -> Result<String, Ooops> {
+    day7::read_from(dir.as_ref()).map_err(in_day::<Day7>)
+}
+
+/// What changed between two day 7 terminal transcripts of the same device
+pub fn diff_filesystems(before: &str, after: &str) -> Result<FsDiff, Ooops> {
+    let before = Day7::parse(before)?;
+    let after = Day7::parse(after)?;
+    Ok(day7::diff(&before, &after))
 }
 
 /// Part A -> <https://adventofcode.com/2022/day/8>
 pub fn trees_visible_from_outside_the_grid(values: &str) -> usize {
-    let trees: Trees = values.into();
-    trees
-        .filter(|tree| {
-            let bigger_than_tree = |l: Tree| l.height >= tree.height;
-            let mut left = LeftTrees {
-                field: values.to_string(),
-                from_left: tree.left,
-                from_top: tree.top,
-            };
-            let mut right = RightTrees {
-                field: values.to_string(),
-                from_left: tree.left,
-                from_top: tree.top,
-            };
-            let mut top = TopTrees {
-                field: values.to_string(),
-                from_left: tree.left,
-                from_top: tree.top,
-            };
-            let mut bottom = BottomTrees {
-                field: values.to_string(),
-                from_left: tree.left,
-                from_top: tree.top,
-            };
-            !left.any(bigger_than_tree)
-                || !right.any(bigger_than_tree)
-                || !bottom.any(bigger_than_tree)
-                || !top.any(bigger_than_tree)
-        })
-        .count()
+    Day8::solve(values, Part::A).expect("day 8 skips invalid trees and rows")
 }
 
 /// Part B -> <https://adventofcode.com/2022/day/8>
 pub fn highest_scenic_score_possible(values: &str) -> usize {
-    let trees: Trees = values.into();
-    trees
-        .map(|t| {
-            viewing_distance(values.to_string(), &t, Direction::Down)
-                * viewing_distance(values.to_string(), &t, Direction::Up)
-                * viewing_distance(values.to_string(), &t, Direction::Left)
-                * viewing_distance(values.to_string(), &t, Direction::Right)
-        })
-        .max()
-        .unwrap_or(0)
+    Day8::solve(values, Part::B).expect("day 8 skips invalid trees and rows")
+}
+
+/// Whether each tree can be seen from outside the grid, as in <https://adventofcode.com/2022/day/8>
+pub fn visible_trees(values: &str) -> Result<Grid<bool>, Ooops> {
+    let forest = Day8::parse(values)?;
+    Ok(forest.visibility())
+}
+
+/// Scenic score of each tree, as in <https://adventofcode.com/2022/day/8>
+pub fn scenic_scores(values: &str) -> Result<Grid<usize>, Ooops> {
+    let forest = Day8::parse(values)?;
+    Ok(forest.scenic_scores())
+}
+
+/// Coordinates and scenic score of the best tree, `None` for an empty grid
+pub fn best_scenic_spot(values: &str) -> Result<Option<(Coord, usize)>, Ooops> {
+    let forest = Day8::parse(values)?;
+    Ok(forest.best_spot())
+}
+
+/// Draws the visible trees as `#` and the hidden ones as `.`
+pub fn visible_trees_ascii(values: &str) -> Result<String, Ooops> {
+    visible_trees(values).map(|visible| day8::ascii(&visible))
+}
+
+/// Scenic scores as CSV, one line per row of trees
+pub fn scenic_scores_csv(values: &str) -> Result<String, Ooops> {
+    scenic_scores(values).map(|scores| day8::csv(&scores))
+}
+
+/// Writes the scenic scores as a PPM heatmap, one pixel per tree
+pub fn export_scenic_heatmap(values: &str, path: impl AsRef<Path>) -> Result<(), Ooops> {
+    let scores = scenic_scores(values)?;
+    write_file(path.as_ref(), |out| day8::write_ppm(&scores, out)).map_err(in_day::<Day8>)
 }
 
 /// Part A -> <https://adventofcode.com/2022/day/9>
 pub fn tail_visits(values: &str) -> Result<usize, Ooops> {
-    let mut rope: Rope = Default::default();
-    for line in values.lines() {
-        rope = move_head(rope, line.parse()?);
-    }
-    let tail_positions: HashSet<Position> = HashSet::from_iter(rope.tail.iter().cloned());
-    Ok(tail_positions.len())
+    Day9::solve(values, Part::A)
+}
+
+/// Part B (with `knots` = 10) -> <https://adventofcode.com/2022/day/9>
+pub fn tail_visits_with_knots(values: &str, knots: usize) -> Result<usize, Ooops> {
+    day9::check_knots(knots).map_err(in_day::<Day9>)?;
+    let movements = Day9::parse(values)?;
+    Ok(day9::tail_visits(&movements, knots))
+}
+
+/// Draws the rope with `knots` knots after `step` steps of the head, as in <https://adventofcode.com/2022/day/9>
+pub fn rope_frame(values: &str, knots: usize, step: usize) -> Result<String, Ooops> {
+    day9::check_knots(knots).map_err(in_day::<Day9>)?;
+    let movements = Day9::parse(values)?;
+    let history = RopeHistory::record(&movements, knots);
+    history.frame(step).ok_or_else(|| {
+        Ooops::new(ErrorKind::OutOfRange("step"), step.to_string()).in_day(Day9::DAY)
+    })
+}
+
+/// Writes the cells visited by the tail of a rope with `knots` knots as a PGM image
+pub fn export_tail_visits(values: &str, knots: usize, path: impl AsRef<Path>) -> Result<(), Ooops> {
+    day9::check_knots(knots).map_err(in_day::<Day9>)?;
+    let movements = Day9::parse(values)?;
+    let history = RopeHistory::record(&movements, knots);
+    write_file(path.as_ref(), |out| history.write_pgm(out)).map_err(in_day::<Day9>)
 }
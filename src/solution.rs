@@ -0,0 +1,279 @@
+use std::{fmt::Display, str::FromStr};
+
+use crate::{
+    day1::Day1,
+    day2::Day2,
+    day3::Day3,
+    day4::Day4,
+    day5::Day5,
+    day6::Day6,
+    day7::Day7,
+    day8::Day8,
+    day9::Day9,
+    error::{ErrorKind, Missing, Ooops},
+};
+
+/// Puzzle part
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Part {
+    /// First half of the puzzle
+    A,
+    /// Second half of the puzzle
+    B,
+}
+
+impl FromStr for Part {
+    type Err = Ooops;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "a" | "A" => Ok(Part::A),
+            "b" | "B" => Ok(Part::B),
+            _ => Err(Ooops::parse("part", s)),
+        }
+    }
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Part::A => f.pad("a"),
+            Part::B => f.pad("b"),
+        }
+    }
+}
+
+/// How lines that cannot be parsed are handled
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Mode {
+    /// Fail with the first invalid line
+    Strict,
+    /// Skip invalid lines, keeping track of them
+    #[default]
+    Lenient,
+}
+
+/// Collects the lines skipped while parsing
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Diagnostics {
+    mode: Mode,
+    skipped: Vec<Ooops>,
+}
+
+impl Diagnostics {
+    /// Empty diagnostics for the given mode
+    pub fn new(mode: Mode) -> Self {
+        Self {
+            mode,
+            skipped: vec![],
+        }
+    }
+
+    /// Lines skipped so far
+    pub fn skipped(&self) -> &[Ooops] {
+        &self.skipped
+    }
+
+    /// Fails in [`Mode::Strict`], otherwise records `e` and lets the parser move on
+    pub(crate) fn skip(&mut self, e: Ooops) -> Result<(), Ooops> {
+        match self.mode {
+            Mode::Strict => Err(e),
+            Mode::Lenient => {
+                self.skipped.push(e);
+                Ok(())
+            }
+        }
+    }
+
+    /// Parses every non-empty line of `values`, skipping the invalid ones
+    pub(crate) fn parse_lines<T>(
+        &mut self,
+        values: &str,
+        parse: impl Fn(&str) -> Result<T, Ooops>,
+    ) -> Result<Vec<T>, Ooops> {
+        let mut result = vec![];
+        for (index, line) in values.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            match parse(line) {
+                Ok(value) => result.push(value),
+                Err(e) => self.skip(e.at_line(index + 1))?,
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// An answer together with the lines skipped to get it
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Diagnosed<T> {
+    /// The puzzle answer
+    pub answer: T,
+    /// Lines that could not be parsed
+    pub skipped: Vec<Ooops>,
+}
+
+/// A puzzle day, parsed once and solved for both parts
+pub trait Solution {
+    /// The puzzle day (1-25)
+    const DAY: usize;
+    /// Parsed puzzle input
+    type Input;
+    /// Puzzle answer
+    type Output: Display;
+
+    /// Turns the raw puzzle input into [`Solution::Input`], reporting invalid lines to `diagnostics`
+    fn parse_with(values: &str, diagnostics: &mut Diagnostics) -> Result<Self::Input, Ooops>;
+
+    /// Turns the raw puzzle input into [`Solution::Input`], silently skipping invalid lines. Errors
+    /// carry the day
+    fn parse(values: &str) -> Result<Self::Input, Ooops> {
+        Self::parse_with(values, &mut Diagnostics::new(Mode::Lenient))
+            .map_err(|e| e.in_day(Self::DAY))
+    }
+
+    /// Like [`Solution::parse_with`], for days whose parts read the input differently. Only the
+    /// lines the given part can use are kept
+    fn parse_part(
+        values: &str,
+        _part: Part,
+        diagnostics: &mut Diagnostics,
+    ) -> Result<Self::Input, Ooops> {
+        Self::parse_with(values, diagnostics)
+    }
+
+    /// Part A of the puzzle
+    fn part_a(input: &Self::Input) -> Result<Self::Output, Ooops>;
+
+    /// Part B of the puzzle
+    fn part_b(input: &Self::Input) -> Result<Self::Output, Ooops>;
+
+    /// Parses the raw input and solves the given part
+    fn solve(values: &str, part: Part) -> Result<Self::Output, Ooops> {
+        Self::solve_with(values, part, Mode::Lenient).map(|d| d.answer)
+    }
+
+    /// Parses the raw input in the given mode and solves the given part
+    fn solve_with(values: &str, part: Part, mode: Mode) -> Result<Diagnosed<Self::Output>, Ooops> {
+        let mut diagnostics = Diagnostics::new(mode);
+        let input =
+            Self::parse_part(values, part, &mut diagnostics).map_err(|e| e.in_day(Self::DAY))?;
+        let answer = match part {
+            Part::A => Self::part_a(&input),
+            Part::B => Self::part_b(&input),
+        }
+        .map_err(|e| e.in_day(Self::DAY))?;
+        Ok(Diagnosed {
+            answer,
+            skipped: diagnostics
+                .skipped
+                .into_iter()
+                .map(|e| e.in_day(Self::DAY))
+                .collect(),
+        })
+    }
+}
+
+/// Object safe view of a [`Solution`], so that days can be stored side by side
+pub trait Puzzle {
+    /// The puzzle day (1-25)
+    fn day(&self) -> usize;
+
+    /// Solves the given part, rendering the answer as text
+    fn run(&self, part: Part, values: &str) -> Result<String, Ooops> {
+        self.run_with(part, values, Mode::Lenient).map(|d| d.answer)
+    }
+
+    /// Solves the given part in the given mode, rendering the answer as text
+    fn run_with(&self, part: Part, values: &str, mode: Mode) -> Result<Diagnosed<String>, Ooops>;
+}
+
+impl<S: Solution> Puzzle for S {
+    fn day(&self) -> usize {
+        S::DAY
+    }
+
+    fn run_with(&self, part: Part, values: &str, mode: Mode) -> Result<Diagnosed<String>, Ooops> {
+        S::solve_with(values, part, mode).map(|d| Diagnosed {
+            answer: d.answer.to_string(),
+            skipped: d.skipped,
+        })
+    }
+}
+
+/// Every solved day, in order
+pub const PUZZLES: [&dyn Puzzle; 9] = [
+    &Day1, &Day2, &Day3, &Day4, &Day5, &Day6, &Day7, &Day8, &Day9,
+];
+
+/// Looks up a day by its number
+pub fn puzzle(day: usize) -> Option<&'static dyn Puzzle> {
+    PUZZLES.iter().find(|p| p.day() == day).copied()
+}
+
+/// Runs the given day and part against `values`
+pub fn run(day: usize, part: Part, values: &str) -> Result<String, Ooops> {
+    run_with(day, part, values, Mode::Lenient).map(|d| d.answer)
+}
+
+/// Runs the given day and part against `values` in the given mode
+pub fn run_with(
+    day: usize,
+    part: Part,
+    values: &str,
+    mode: Mode,
+) -> Result<Diagnosed<String>, Ooops> {
+    puzzle(day)
+        .ok_or_else(|| Ooops::new(ErrorKind::NotFound(Missing::Solution), day.to_string()))?
+        .run_with(part, values, mode)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registry_is_sorted() {
+        for (index, puzzle) in PUZZLES.iter().enumerate() {
+            assert_eq!(index + 1, puzzle.day());
+        }
+    }
+
+    #[test]
+    fn parse_part() {
+        assert_eq!(Ok(Part::A), "a".parse());
+        assert_eq!(Ok(Part::B), "B".parse());
+        assert_eq!(Err(Ooops::parse("part", "c")), "c".parse::<Part>());
+    }
+
+    #[test]
+    fn run_by_number() {
+        assert_eq!(Ok("69693".to_string()), run(1, Part::A, crate::input::DAY1));
+        assert_eq!(
+            Ok("PWPWHGFZS".to_string()),
+            run(5, Part::B, crate::input::DAY5)
+        );
+        assert_eq!(
+            Err(Ooops::new(ErrorKind::NotFound(Missing::Solution), "25")),
+            run(25, Part::A, "")
+        );
+    }
+
+    #[test]
+    fn strict_and_lenient() {
+        let values = "1-2,2-3\n1-4,x\n2-8,3-7\n";
+        assert_eq!(
+            Err(Ooops::parse("range", "x").at_line(2).in_day(4)),
+            run_with(4, Part::A, values, Mode::Strict)
+        );
+        assert_eq!(
+            Ok(Diagnosed {
+                answer: "1".to_string(),
+                skipped: vec![Ooops::parse("range", "x").at_line(2).in_day(4)]
+            }),
+            run_with(4, Part::A, values, Mode::Lenient)
+        );
+    }
+}